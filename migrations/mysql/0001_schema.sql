@@ -0,0 +1,50 @@
+-- instruments, price bars, transactions ledger and current positions
+
+CREATE TABLE instruments (
+    ticker VARCHAR(32) NOT NULL PRIMARY KEY,
+    name VARCHAR(255),
+    currency CHAR(3) NOT NULL,
+    exchange VARCHAR(32),
+    quote_type VARCHAR(32)
+);
+
+-- ohlcv bars keyed by ticker, bar start (unix seconds) and interval code (e.g. 1d, 1h)
+CREATE TABLE prices (
+    ticker VARCHAR(32) NOT NULL,
+    ts BIGINT NOT NULL,
+    bar_interval VARCHAR(8) NOT NULL DEFAULT '1d',
+    timezone VARCHAR(64) NOT NULL DEFAULT 'UTC',
+    open DOUBLE NOT NULL,
+    high DOUBLE NOT NULL,
+    low DOUBLE NOT NULL,
+    close DOUBLE NOT NULL,
+    adjclose DOUBLE NOT NULL,
+    volume BIGINT NOT NULL DEFAULT 0,
+    PRIMARY KEY (ticker, ts, bar_interval),
+    FOREIGN KEY (ticker) REFERENCES instruments (ticker)
+);
+
+-- cash events (dividend, fee, deposit, withdrawal) use `amount`, splits store the ratio in `quantity`
+CREATE TABLE transactions (
+    id BIGINT NOT NULL AUTO_INCREMENT PRIMARY KEY,
+    trade_date DATE NOT NULL,
+    kind VARCHAR(16) NOT NULL,
+    ticker VARCHAR(32),
+    quantity DECIMAL(20, 8) NOT NULL DEFAULT 0,
+    price DECIMAL(20, 8) NOT NULL DEFAULT 0,
+    amount DECIMAL(20, 8) NOT NULL DEFAULT 0,
+    fees DECIMAL(20, 8) NOT NULL DEFAULT 0,
+    currency CHAR(3) NOT NULL,
+    note VARCHAR(255),
+    CHECK (kind IN ('buy', 'sell', 'dividend', 'fee', 'deposit', 'withdrawal', 'split')),
+    FOREIGN KEY (ticker) REFERENCES instruments (ticker),
+    INDEX idx_transactions_ticker_date (ticker, trade_date)
+);
+
+CREATE TABLE positions (
+    ticker VARCHAR(32) NOT NULL PRIMARY KEY,
+    quantity DECIMAL(20, 8) NOT NULL,
+    buy_date DATE NOT NULL,
+    buy_price DECIMAL(20, 8) NOT NULL,
+    FOREIGN KEY (ticker) REFERENCES instruments (ticker)
+);
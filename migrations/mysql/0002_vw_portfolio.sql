@@ -0,0 +1,36 @@
+-- one row per position valued at the latest daily close, the buy price is used when no price is stored
+
+CREATE VIEW vw_portfolio AS
+SELECT
+    v.ticker,
+    v.quantity,
+    i.currency,
+    v.buy_date,
+    v.buy_price,
+    v.quantity * v.buy_price AS buy_value,
+    v.market_price,
+    v.quantity * v.market_price AS market_value,
+    v.quantity * (v.market_price - v.buy_price) AS PL,
+    CASE
+        WHEN v.buy_price = 0 THEN 0
+        ELSE (v.market_price - v.buy_price) / v.buy_price
+    END AS PL_pct
+FROM (
+    SELECT
+        pos.ticker,
+        pos.quantity,
+        pos.buy_date,
+        pos.buy_price,
+        COALESCE(
+            (
+                SELECT CAST(p.close AS DECIMAL(20, 8))
+                FROM prices p
+                WHERE p.ticker = pos.ticker AND p.bar_interval = '1d'
+                ORDER BY p.ts DESC
+                LIMIT 1
+            ),
+            pos.buy_price
+        ) AS market_price
+    FROM positions pos
+) v
+JOIN instruments i ON i.ticker = v.ticker;
@@ -0,0 +1,51 @@
+-- instruments, price bars, transactions ledger and current positions
+
+CREATE TABLE instruments (
+    ticker TEXT NOT NULL PRIMARY KEY,
+    name TEXT,
+    currency TEXT NOT NULL,
+    exchange TEXT,
+    quote_type TEXT
+);
+
+-- ohlcv bars keyed by ticker, bar start (unix seconds) and interval code (e.g. 1d, 1h)
+CREATE TABLE prices (
+    ticker TEXT NOT NULL REFERENCES instruments (ticker),
+    ts INTEGER NOT NULL,
+    bar_interval TEXT NOT NULL DEFAULT '1d',
+    timezone TEXT NOT NULL DEFAULT 'UTC',
+    open REAL NOT NULL,
+    high REAL NOT NULL,
+    low REAL NOT NULL,
+    close REAL NOT NULL,
+    adjclose REAL NOT NULL,
+    volume INTEGER NOT NULL DEFAULT 0,
+    PRIMARY KEY (ticker, ts, bar_interval)
+);
+
+-- cash events (dividend, fee, deposit, withdrawal) use `amount`, splits store the ratio in `quantity`
+-- sqlite has no decimal type, decimals are stored as text so they round-trip exactly
+-- (numeric affinity would convert them into floating point)
+CREATE TABLE transactions (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    trade_date DATE NOT NULL,
+    kind TEXT NOT NULL,
+    ticker TEXT REFERENCES instruments (ticker),
+    quantity TEXT NOT NULL DEFAULT '0',
+    price TEXT NOT NULL DEFAULT '0',
+    amount TEXT NOT NULL DEFAULT '0',
+    fees TEXT NOT NULL DEFAULT '0',
+    currency TEXT NOT NULL,
+    note TEXT,
+    CHECK (kind IN ('buy', 'sell', 'dividend', 'fee', 'deposit', 'withdrawal', 'split'))
+);
+
+CREATE INDEX idx_transactions_ticker_date ON transactions (ticker, trade_date);
+
+-- decimals stored as text, like the transactions ledger
+CREATE TABLE positions (
+    ticker TEXT NOT NULL PRIMARY KEY REFERENCES instruments (ticker),
+    quantity TEXT NOT NULL,
+    buy_date DATE NOT NULL,
+    buy_price TEXT NOT NULL
+);
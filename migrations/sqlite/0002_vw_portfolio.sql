@@ -0,0 +1,41 @@
+-- one row per position valued at the latest daily close, the buy price is used when no price is stored
+-- the return is computed in floating point, sqlite truncates the division of whole numbers
+-- quantity and buy price are passed through as stored text, computed values are floating point
+-- rendered by sqlite with 15 significant digits, `portfolio_table` recomputes them exactly from the
+-- text columns
+-- text is compared as a number: the text '0.00' is not equal to the integer 0
+
+CREATE VIEW vw_portfolio AS
+SELECT
+    v.ticker,
+    v.quantity,
+    i.currency,
+    v.buy_date,
+    v.buy_price,
+    v.quantity * v.buy_price AS buy_value,
+    v.market_price,
+    v.quantity * v.market_price AS market_value,
+    v.quantity * (v.market_price - v.buy_price) AS PL,
+    CASE
+        WHEN CAST(v.buy_price AS REAL) = 0 THEN 0
+        ELSE CAST(v.market_price - v.buy_price AS REAL) / v.buy_price
+    END AS PL_pct
+FROM (
+    SELECT
+        pos.ticker,
+        pos.quantity,
+        pos.buy_date,
+        pos.buy_price,
+        COALESCE(
+            (
+                SELECT CAST(p.close AS NUMERIC)
+                FROM prices p
+                WHERE p.ticker = pos.ticker AND p.bar_interval = '1d'
+                ORDER BY p.ts DESC
+                LIMIT 1
+            ),
+            pos.buy_price
+        ) AS market_price
+    FROM positions pos
+) v
+JOIN instruments i ON i.ticker = v.ticker;
@@ -1,20 +1,36 @@
+use crate::data::yahoo::QuoteItem;
+use ndarray::Array1;
 use thiserror::Error;
 use time::macros::format_description;
-use time::{Date, OffsetDateTime, PrimitiveDateTime, Time, UtcOffset};
+use time::{Date, OffsetDateTime, PrimitiveDateTime, Time, UtcOffset, Weekday};
+use time_tz::{timezones, OffsetDateTimeExt, PrimitiveDateTimeExt};
 
 // datetime parsing errors
 #[derive(Debug, Error)]
 pub enum DateTimeError {
     #[error("Failed to parse date '{0}': {1}")]
-    InvalidDateFormat(String, String),
+    InvalidDateFormat(String, #[source] time::error::Parse),
     #[error("Failed to convert timestamp '{0}' into datetime: {1}")]
-    InvalidTimestamp(i64, String),
+    InvalidTimestamp(i64, #[source] time::error::ComponentRange),
     #[error("Invalid datetime format. Expected %Y-%m-%d %H:%M:%S, but got '{0}'")]
     InvalidDateTimeFormat(String),
     #[error("Invalid time component: {0}")]
-    InvalidTimeComponent(String),
+    InvalidTimeComponent(#[source] time::error::ComponentRange),
+    #[error("Unknown timezone '{0}'")]
+    UnknownTimezone(String),
+    #[error("Local datetime '{0}' does not exist in timezone '{1}'")]
+    NonexistentLocalTime(String, String),
+    #[error("Days since epoch '{0}' out of the supported date range: {1}")]
+    InvalidEpochDays(i32, #[source] time::error::ComponentRange),
+    #[error("Nanoseconds since epoch '{0}' out of the supported datetime range: {1}")]
+    InvalidEpochNanos(i128, #[source] time::error::ComponentRange),
+    #[error("Quotes mix timezones '{0}' and '{1}', a datetime column holds a single timezone")]
+    MixedTimezones(String, String),
 }
 
+// julian day of 1970-01-01, columnar formats (arrow, polars) store dates as days since unix epoch
+const EPOCH_JULIAN_DAY: i32 = 2_440_588;
+
 pub enum DateType {
     Start,
     End,
@@ -25,20 +41,18 @@ pub fn parse_date(date: &str, date_type: DateType) -> Result<OffsetDateTime, Dat
     match date_type {
         DateType::Start => {
             let date_fmt = format_description!("[year]-[month]-[day]");
-            let date = Date::parse(date, date_fmt).map_err(|err| {
-                DateTimeError::InvalidDateFormat(date.to_string(), err.to_string())
-            })?;
+            let date = Date::parse(date, date_fmt)
+                .map_err(|err| DateTimeError::InvalidDateFormat(date.to_string(), err))?;
             let primitive_dt = PrimitiveDateTime::new(date, Time::MIDNIGHT);
             let dt_utc = primitive_dt.assume_offset(UtcOffset::UTC);
             Ok(dt_utc)
         }
         DateType::End => {
             let date_fmt = format_description!("[year]-[month]-[day]");
-            let date = Date::parse(date, date_fmt).map_err(|err| {
-                DateTimeError::InvalidDateFormat(date.to_string(), err.to_string())
-            })?;
+            let date = Date::parse(date, date_fmt)
+                .map_err(|err| DateTimeError::InvalidDateFormat(date.to_string(), err))?;
             let eod = Time::from_hms_micro(23, 59, 59, 999_999)
-                .map_err(|err| DateTimeError::InvalidTimeComponent(err.to_string()))?;
+                .map_err(DateTimeError::InvalidTimeComponent)?;
             let primitive_dt = PrimitiveDateTime::new(date, eod);
             let dt_utc = primitive_dt.assume_offset(UtcOffset::UTC);
             Ok(dt_utc)
@@ -49,22 +63,30 @@ pub fn parse_date(date: &str, date_type: DateType) -> Result<OffsetDateTime, Dat
 // convert timestamp into datetime
 pub fn timestamp_to_datetime(timestamp: i64) -> Result<OffsetDateTime, DateTimeError> {
     Ok(OffsetDateTime::from_unix_timestamp(timestamp)
-        .map_err(|err| DateTimeError::InvalidTimestamp(timestamp, err.to_string()))?
+        .map_err(|err| DateTimeError::InvalidTimestamp(timestamp, err))?
         .to_offset(UtcOffset::UTC))
 }
 
-// convert timestamp into local datetime string in the format "Y-m-d H:M:S"
-pub fn timestamp_to_localdt(timestamp: u64) -> Result<String, DateTimeError> {
-    let utc_datetime = timestamp_to_datetime(timestamp as i64)?;
-    let local_datetime = utc_datetime.to_offset(
-        UtcOffset::from_hms(1, 0, 0)
-            .map_err(|err| DateTimeError::InvalidTimestamp(timestamp as i64, err.to_string()))?,
-    );
-    let format = format_description!("[year]-[month]-[day] [hour]:[minute]:[second]");
-    let dt = local_datetime
-        .format(&format)
-        .map_err(|err| DateTimeError::InvalidTimestamp(timestamp as i64, err.to_string()))?;
-    Ok(dt)
+// convert timestamp into datetime in the given exchange timezone (IANA name, e.g. "Europe/Rome")
+pub fn timestamp_to_exchange_dt(
+    timestamp: u64,
+    timezone: &str,
+) -> Result<OffsetDateTime, DateTimeError> {
+    let tz = timezones::get_by_name(timezone)
+        .ok_or_else(|| DateTimeError::UnknownTimezone(timezone.to_string()))?;
+    Ok(timestamp_to_datetime(timestamp as i64)?.to_timezone(tz))
+}
+
+// attach exchange timezone (IANA name) to a local datetime, ambiguous DST times resolve to the earlier offset
+pub fn local_to_exchange_dt(
+    datetime: PrimitiveDateTime,
+    timezone: &str,
+) -> Result<OffsetDateTime, DateTimeError> {
+    let tz = timezones::get_by_name(timezone)
+        .ok_or_else(|| DateTimeError::UnknownTimezone(timezone.to_string()))?;
+    datetime.assume_timezone(tz).take_first().ok_or_else(|| {
+        DateTimeError::NonexistentLocalTime(datetime.to_string(), timezone.to_string())
+    })
 }
 
 // convert datetime into simple date
@@ -74,3 +96,56 @@ pub fn datetime_to_date(d: String) -> Result<String, DateTimeError> {
         None => Err(DateTimeError::InvalidDateTimeFormat(d)),
     }
 }
+
+// convert date into days since unix epoch
+pub fn date_to_epoch_days(date: Date) -> i32 {
+    date.to_julian_day() - EPOCH_JULIAN_DAY
+}
+
+// convert days since unix epoch into date
+pub fn epoch_days_to_date(days: i32) -> Result<Date, DateTimeError> {
+    Date::from_julian_day(days + EPOCH_JULIAN_DAY)
+        .map_err(|err| DateTimeError::InvalidEpochDays(days, err))
+}
+
+// convert datetime into microseconds since unix epoch, sub-microsecond precision is truncated
+pub fn datetime_to_micros(datetime: OffsetDateTime) -> i64 {
+    (datetime.unix_timestamp_nanos() / 1_000) as i64
+}
+
+// convert nanoseconds since unix epoch into datetime in the given exchange timezone (IANA name)
+pub fn nanos_to_exchange_dt(nanos: i128, timezone: &str) -> Result<OffsetDateTime, DateTimeError> {
+    let tz = timezones::get_by_name(timezone)
+        .ok_or_else(|| DateTimeError::UnknownTimezone(timezone.to_string()))?;
+    Ok(OffsetDateTime::from_unix_timestamp_nanos(nanos)
+        .map_err(|err| DateTimeError::InvalidEpochNanos(nanos, err))?
+        .to_timezone(tz))
+}
+
+// timezone shared by every quote (utc when there are no quotes), quotes in different timezones are rejected
+pub fn single_timezone(quotes: &Array1<QuoteItem>) -> Result<&str, DateTimeError> {
+    let timezone = quotes.first().map_or("UTC", |q| q.timezone.as_str());
+    match quotes.iter().find(|q| q.timezone != timezone) {
+        Some(q) => Err(DateTimeError::MixedTimezones(
+            timezone.to_string(),
+            q.timezone.clone(),
+        )),
+        None => Ok(timezone),
+    }
+}
+
+// saturdays and sundays in [start, end], the closed days of most exchange calendars
+pub fn weekends(start: Date, end: Date) -> Vec<Date> {
+    let mut days = Vec::new();
+    let mut date = start;
+    while date <= end {
+        if matches!(date.weekday(), Weekday::Saturday | Weekday::Sunday) {
+            days.push(date);
+        }
+        date = match date.next_day() {
+            Some(d) => d,
+            None => break,
+        };
+    }
+    days
+}
@@ -0,0 +1,61 @@
+//! presentation helpers
+//! data and quantitative modules keep values at full precision, rounding is opt-in for display and reporting
+use super::parser::round_to;
+use crate::data::yahoo::{MultiQuoteItem, QuoteItem};
+use ndarray::Array1;
+use std::{collections::HashMap, hash::Hash};
+
+// trait to round values to `decimals` digits for presentation
+pub trait Round {
+    fn rounded(&self, decimals: u32) -> Self;
+}
+
+impl Round for f64 {
+    fn rounded(&self, decimals: u32) -> Self {
+        round_to(*self, decimals)
+    }
+}
+
+// round prices, volume is left untouched
+impl Round for QuoteItem {
+    fn rounded(&self, decimals: u32) -> Self {
+        Self {
+            open: self.open.rounded(decimals),
+            high: self.high.rounded(decimals),
+            low: self.low.rounded(decimals),
+            close: self.close.rounded(decimals),
+            adjclose: self.adjclose.rounded(decimals),
+            ..self.clone()
+        }
+    }
+}
+
+impl Round for MultiQuoteItem {
+    fn rounded(&self, decimals: u32) -> Self {
+        Self {
+            date: self.date,
+            prices: self.prices.rounded(decimals),
+        }
+    }
+}
+
+impl<K: Clone + Eq + Hash, V: Round> Round for HashMap<K, V> {
+    fn rounded(&self, decimals: u32) -> Self {
+        self.iter()
+            .map(|(k, v)| (k.clone(), v.rounded(decimals)))
+            .collect()
+    }
+}
+
+// round the value of keyed tuples, e.g. returns as (datetime, value) or (date, values by ticker)
+impl<K: Clone, V: Round> Round for (K, V) {
+    fn rounded(&self, decimals: u32) -> Self {
+        (self.0.clone(), self.1.rounded(decimals))
+    }
+}
+
+impl<T: Round> Round for Array1<T> {
+    fn rounded(&self, decimals: u32) -> Self {
+        self.map(|x| x.rounded(decimals))
+    }
+}
@@ -1,3 +1,4 @@
 pub mod date;
+pub mod format;
 pub mod parser;
 pub mod utils;
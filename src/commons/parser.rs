@@ -7,3 +7,9 @@ pub fn round_to_three(n: f64) -> f64 {
 pub fn round_to_four(n: f64) -> f64 {
     (n * 10000.0).round() / 10000.0
 }
+
+// helper function to round f64 to `decimals` digits
+pub fn round_to(n: f64, decimals: u32) -> f64 {
+    let factor = 10f64.powi(decimals as i32);
+    (n * factor).round() / factor
+}
@@ -0,0 +1,122 @@
+//! missing-data alignment policies for multi-asset quotes
+use super::yahoo::MultiQuoteItem;
+use ndarray::Array1;
+use std::collections::{BTreeSet, HashMap};
+
+// alignment policy applied when tickers trade on different calendars
+// a price is considered missing when the ticker key is absent from the row or its value is NaN
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum AlignPolicy {
+    // keep only dates where every ticker has a key, NaN values are left untouched
+    Inner,
+    // keep every date and carry the last known price forward,
+    // leading rows where a ticker has no price yet are dropped
+    ForwardFill,
+    // drop every row with at least one missing price
+    DropMissing,
+    // keep every date and replace missing prices with a constant value
+    Fill(f64),
+}
+
+// struct to model alignment outcome, counters are keyed by ticker
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct AlignReport {
+    pub missing: HashMap<String, usize>,
+    pub filled: HashMap<String, usize>,
+    pub dropped_rows: usize,
+}
+
+impl AlignReport {
+    // total number of prices filled across all tickers
+    pub fn total_filled(&self) -> usize {
+        self.filled.values().sum()
+    }
+}
+
+pub trait Align {
+    fn align(&self, policy: AlignPolicy) -> (Array1<MultiQuoteItem>, AlignReport);
+}
+
+impl Align for Array1<MultiQuoteItem> {
+    // align multiquotes so that every returned row holds a price for each ticker found in the input
+    fn align(&self, policy: AlignPolicy) -> (Array1<MultiQuoteItem>, AlignReport) {
+        let tickers: BTreeSet<&String> = self.iter().flat_map(|q| q.prices.keys()).collect();
+        let mut report = AlignReport::default();
+        for q in self {
+            for t in &tickers {
+                if price(q, t).is_none() {
+                    *report.missing.entry(t.to_string()).or_default() += 1;
+                }
+            }
+        }
+
+        let mut last: HashMap<&String, f64> = HashMap::new();
+        let mut aligned = Vec::with_capacity(self.len());
+        for q in self {
+            let row = match policy {
+                AlignPolicy::Inner => tickers
+                    .iter()
+                    .all(|t| q.prices.contains_key(*t))
+                    .then(|| q.clone()),
+                AlignPolicy::DropMissing => tickers
+                    .iter()
+                    .all(|t| price(q, t).is_some())
+                    .then(|| q.clone()),
+                AlignPolicy::ForwardFill => {
+                    let mut prices = HashMap::with_capacity(tickers.len());
+                    let mut filled = Vec::new();
+                    for t in &tickers {
+                        match price(q, t) {
+                            Some(p) => {
+                                last.insert(*t, p);
+                                prices.insert(t.to_string(), p);
+                            }
+                            None => {
+                                if let Some(p) = last.get(*t) {
+                                    prices.insert(t.to_string(), *p);
+                                    filled.push(*t);
+                                }
+                            }
+                        }
+                    }
+                    if prices.len() == tickers.len() {
+                        for t in filled {
+                            *report.filled.entry(t.to_string()).or_default() += 1;
+                        }
+                        Some(MultiQuoteItem {
+                            date: q.date,
+                            prices,
+                        })
+                    } else {
+                        None
+                    }
+                }
+                AlignPolicy::Fill(value) => Some(MultiQuoteItem {
+                    date: q.date,
+                    prices: tickers
+                        .iter()
+                        .map(|t| {
+                            let p = price(q, t).unwrap_or_else(|| {
+                                *report.filled.entry(t.to_string()).or_default() += 1;
+                                value
+                            });
+                            (t.to_string(), p)
+                        })
+                        .collect(),
+                }),
+            };
+
+            match row {
+                Some(r) => aligned.push(r),
+                None => report.dropped_rows += 1,
+            }
+        }
+
+        (Array1::from_vec(aligned), report)
+    }
+}
+
+// helper function to read a ticker price, NaN values are treated as missing
+fn price(quote: &MultiQuoteItem, ticker: &str) -> Option<f64> {
+    quote.prices.get(ticker).copied().filter(|p| !p.is_nan())
+}
@@ -0,0 +1,339 @@
+//! alpha vantage market data provider
+use super::batch::{get_quotes_batch, BatchConfig};
+use super::provider::MarketDataProvider;
+use super::request::Range;
+use super::yahoo::{group_by_date, MultiQuoteItem, OptionContract, OptionType, QuoteItem};
+use crate::commons::date::{local_to_exchange_dt, parse_date, DateTimeError, DateType};
+use dotenvy::dotenv;
+use ndarray::Array1;
+use reqwest::Client;
+use serde_json::{Map, Value};
+use std::env;
+use thiserror::Error;
+use time::{macros::format_description, Date, OffsetDateTime, PrimitiveDateTime, Time};
+
+#[derive(Debug, Error)]
+pub enum AlphaVantageErr {
+    #[error("Alpha Vantage API key not set in environment variables.")]
+    MissingApiKey,
+    #[error("Request to Alpha Vantage servers failed: {0}")]
+    RequestFailed(#[from] reqwest::Error),
+    #[error("Failed to deserialize from Alpha Vantage: {0}")]
+    DeserializationFailed(#[from] serde_json::Error),
+    #[error("Alpha Vantage returned an error: {0}")]
+    ApiError(String),
+    #[error("Alpha Vantage request limit reached: {0}")]
+    RateLimited(String),
+    #[error("Alpha Vantage returned an empty data set.")]
+    EmptyDataSet,
+    #[error("Alpha Vantage returned inconsistent data: {0}")]
+    DataInconsistency(String),
+    #[error("Unsupported interval or period '{0}'")]
+    UnsupportedInterval(String),
+    #[error("Failed to convert Alpha Vantage datetime: {0}")]
+    DateTime(#[from] DateTimeError),
+    #[error("{0} not available from Alpha Vantage")]
+    NotSupported(String),
+}
+
+const AV_BASE_URL: &str = "https://www.alphavantage.co";
+
+// struct to model connection with alpha vantage rest api
+pub struct AlphaVantage {
+    pub client: Client,
+    pub base_url: String,
+    pub api_key: String,
+}
+
+impl AlphaVantage {
+    // create new provider reading the api key from `ALPHAVANTAGE_API_KEY` (environment or .env file)
+    pub fn provider() -> Result<Self, AlphaVantageErr> {
+        dotenv().ok();
+        let api_key =
+            env::var("ALPHAVANTAGE_API_KEY").map_err(|_| AlphaVantageErr::MissingApiKey)?;
+        Ok(Self::new(&api_key))
+    }
+
+    // create new provider with an explicit api key
+    pub fn new(api_key: &str) -> Self {
+        Self {
+            client: Client::new(),
+            base_url: AV_BASE_URL.to_string(),
+            api_key: api_key.to_string(),
+        }
+    }
+
+    // point the provider to another server (e.g. a local mock server)
+    pub fn with_base_url(mut self, base_url: &str) -> Self {
+        self.base_url = base_url.trim_end_matches('/').to_string();
+        self
+    }
+
+    // get quotation data for single asset query
+    // interval defaults to "1d", supported intervals are 1m, 5m, 15m, 30m, 60m, 1d, 1wk and 1mo
+    // dates and period are applied locally since alpha vantage only returns the latest bars
+    pub async fn get_quotes(
+        &self,
+        symbol: &str,
+        start_date: Option<&str>,
+        end_date: Option<&str>,
+        period: Option<&str>,
+        interval: Option<&str>,
+    ) -> Result<Array1<QuoteItem>, AlphaVantageErr> {
+        let interval = interval.unwrap_or("1d");
+        let mut params = vec![("symbol", symbol)];
+        match interval {
+            "1m" | "5m" | "15m" | "30m" | "60m" => {
+                params.push(("function", "TIME_SERIES_INTRADAY"));
+                params.push(("interval", intraday_interval(interval)));
+            }
+            "1d" => params.push(("function", "TIME_SERIES_DAILY")),
+            "1wk" => params.push(("function", "TIME_SERIES_WEEKLY_ADJUSTED")),
+            "1mo" => params.push(("function", "TIME_SERIES_MONTHLY_ADJUSTED")),
+            _ => return Err(AlphaVantageErr::UnsupportedInterval(interval.to_string())),
+        }
+
+        // compact responses only hold the latest 100 bars
+        // bounds are dates in the exchange calendar, compared against the bar local date
+        let (start, end) = match (start_date, end_date, period) {
+            (Some(s), Some(e), _) => (
+                Some(parse_date(s, DateType::Start)?.date()),
+                Some(parse_date(e, DateType::End)?.date()),
+            ),
+            (_, _, Some(p)) => {
+                let now = OffsetDateTime::now_utc();
+                let range: Range = p
+                    .parse()
+                    .map_err(|_| AlphaVantageErr::UnsupportedInterval(p.to_string()))?;
+                (
+                    range
+                        .days(now)
+                        .map(|d| (now - time::Duration::days(d)).date()),
+                    None,
+                )
+            }
+            _ => (None, None),
+        };
+        // any requested range or period (e.g. "max", which has no start) may reach past the compact window
+        let full = start_date.is_some() || end_date.is_some() || period.is_some();
+        params.push(("outputsize", if full { "full" } else { "compact" }));
+
+        let body = self.query(&params).await?;
+        let quotes = convert_time_series(&body)?;
+        Ok(quotes
+            .into_iter()
+            .filter(|q| {
+                start.is_none_or(|s| q.datetime.date() >= s)
+                    && end.is_none_or(|e| q.datetime.date() <= e)
+            })
+            .collect())
+    }
+
+    // get quotation data for multiple assets query
+    pub async fn get_multiple_quotes(
+        &self,
+        tickers: Vec<&str>,
+        start_date: Option<&str>,
+        end_date: Option<&str>,
+        period: Option<&str>,
+        interval: Option<&str>,
+    ) -> Result<Array1<MultiQuoteItem>, AlphaVantageErr> {
+        let batch = get_quotes_batch(
+            self,
+            &tickers,
+            start_date,
+            end_date,
+            period,
+            interval,
+            &BatchConfig::default(),
+        )
+        .await;
+        if let Some((_, e)) = batch.errors.into_iter().next() {
+            return Err(e);
+        }
+        Ok(group_by_date(batch.quotes))
+    }
+
+    // get latest quotation for an asset
+    pub async fn get_latest_quote(&self, ticker: &str) -> Result<f64, AlphaVantageErr> {
+        let body = self
+            .query(&[("function", "GLOBAL_QUOTE"), ("symbol", ticker)])
+            .await?;
+        let price = body
+            .get("Global Quote")
+            .and_then(Value::as_object)
+            .and_then(|q| field(q, "price"))
+            .ok_or(AlphaVantageErr::EmptyDataSet)?;
+        parse_number(price, "price")
+    }
+
+    // send request to alpha vantage query endpoint and check api level errors
+    async fn query(&self, params: &[(&str, &str)]) -> Result<Map<String, Value>, AlphaVantageErr> {
+        let body = self
+            .client
+            .get(format!("{}/query", self.base_url))
+            .query(params)
+            .query(&[("apikey", &self.api_key)])
+            .send()
+            .await?
+            .error_for_status()?
+            .text()
+            .await?;
+        let body: Map<String, Value> = serde_json::from_str(&body)?;
+
+        if let Some(msg) = body.get("Error Message").and_then(Value::as_str) {
+            return Err(AlphaVantageErr::ApiError(msg.to_string()));
+        }
+        if let Some(msg) = body.get("Note").and_then(Value::as_str) {
+            return Err(AlphaVantageErr::RateLimited(msg.to_string()));
+        }
+        // "Information" also carries premium-only endpoint and invalid key notices
+        if let Some(msg) = body.get("Information").and_then(Value::as_str) {
+            let lower = msg.to_lowercase();
+            if lower.contains("rate limit") || lower.contains("call frequency") {
+                return Err(AlphaVantageErr::RateLimited(msg.to_string()));
+            }
+            return Err(AlphaVantageErr::ApiError(msg.to_string()));
+        }
+        Ok(body)
+    }
+}
+
+// alpha vantage as market data provider
+impl MarketDataProvider for AlphaVantage {
+    type Error = AlphaVantageErr;
+
+    async fn get_quotes(
+        &self,
+        symbol: &str,
+        start_date: Option<&str>,
+        end_date: Option<&str>,
+        period: Option<&str>,
+        interval: Option<&str>,
+    ) -> Result<Array1<QuoteItem>, AlphaVantageErr> {
+        AlphaVantage::get_quotes(self, symbol, start_date, end_date, period, interval).await
+    }
+
+    async fn get_multiple_quotes(
+        &self,
+        tickers: Vec<&str>,
+        start_date: Option<&str>,
+        end_date: Option<&str>,
+        period: Option<&str>,
+        interval: Option<&str>,
+    ) -> Result<Array1<MultiQuoteItem>, AlphaVantageErr> {
+        AlphaVantage::get_multiple_quotes(self, tickers, start_date, end_date, period, interval)
+            .await
+    }
+
+    async fn get_latest_quote(&self, ticker: &str) -> Result<f64, AlphaVantageErr> {
+        AlphaVantage::get_latest_quote(self, ticker).await
+    }
+
+    async fn get_options(
+        &self,
+        _ticker: &str,
+        _option_type: OptionType,
+    ) -> Result<Array1<OptionContract>, AlphaVantageErr> {
+        Err(AlphaVantageErr::NotSupported("Options".to_string()))
+    }
+
+    fn is_transient(&self, error: &AlphaVantageErr) -> bool {
+        match error {
+            AlphaVantageErr::RateLimited(_) => true,
+            AlphaVantageErr::RequestFailed(err) => {
+                err.is_connect()
+                    || err.is_timeout()
+                    || err
+                        .status()
+                        .is_some_and(|s| s.as_u16() == 429 || s.is_server_error())
+            }
+            _ => false,
+        }
+    }
+}
+
+// helper function to map interval to alpha vantage intraday interval names
+fn intraday_interval(interval: &str) -> &'static str {
+    match interval {
+        "1m" => "1min",
+        "5m" => "5min",
+        "15m" => "15min",
+        "30m" => "30min",
+        _ => "60min",
+    }
+}
+
+// helper function to convert any alpha vantage time series response into quotes sorted by datetime
+// bars are keyed by exchange local date or datetime, the timezone is read from the response metadata
+fn convert_time_series(body: &Map<String, Value>) -> Result<Array1<QuoteItem>, AlphaVantageErr> {
+    let timezone = body
+        .get("Meta Data")
+        .and_then(Value::as_object)
+        .and_then(|m| field(m, "Time Zone"))
+        .ok_or_else(|| AlphaVantageErr::DataInconsistency("missing timezone".to_string()))?
+        .to_string();
+    let series = body
+        .iter()
+        .find(|(k, _)| k.contains("Time Series"))
+        .and_then(|(_, v)| v.as_object())
+        .ok_or(AlphaVantageErr::EmptyDataSet)?;
+
+    let date_fmt = format_description!("[year]-[month]-[day]");
+    let datetime_fmt = format_description!("[year]-[month]-[day] [hour]:[minute]:[second]");
+    let mut quotes = series
+        .iter()
+        .map(|(dt, bar)| {
+            let bar = bar
+                .as_object()
+                .ok_or_else(|| AlphaVantageErr::DataInconsistency(dt.clone()))?;
+            let local = PrimitiveDateTime::parse(dt, datetime_fmt)
+                .or_else(|_| {
+                    Date::parse(dt, date_fmt).map(|d| PrimitiveDateTime::new(d, Time::MIDNIGHT))
+                })
+                .map_err(|err| DateTimeError::InvalidDateFormat(dt.clone(), err))?;
+            let value = |name: &str| {
+                field(bar, name)
+                    .ok_or_else(|| {
+                        AlphaVantageErr::DataInconsistency(format!("{} missing {}", dt, name))
+                    })
+                    .and_then(|v| parse_number(v, name))
+            };
+            let close = value("close")?;
+            Ok(QuoteItem {
+                datetime: local_to_exchange_dt(local, &timezone)?,
+                timezone: timezone.clone(),
+                open: value("open")?,
+                high: value("high")?,
+                low: value("low")?,
+                close,
+                adjclose: match field(bar, "adjusted close") {
+                    Some(v) => parse_number(v, "adjusted close")?,
+                    None => close,
+                },
+                volume: value("volume")? as u64,
+            })
+        })
+        .collect::<Result<Vec<QuoteItem>, AlphaVantageErr>>()?;
+
+    if quotes.is_empty() {
+        return Err(AlphaVantageErr::EmptyDataSet);
+    }
+    quotes.sort_by_key(|q| q.datetime);
+    Ok(Array1::from_vec(quotes))
+}
+
+// helper function to read a field whose key is prefixed by a sequence number (e.g. "4. close", "05. price")
+fn field<'a>(object: &'a Map<String, Value>, name: &str) -> Option<&'a str> {
+    object
+        .iter()
+        .find(|(k, _)| k.split_once(". ").is_some_and(|(_, n)| n == name))
+        .and_then(|(_, v)| v.as_str())
+}
+
+// helper function to parse numeric fields, alpha vantage returns every number as string
+fn parse_number(value: &str, name: &str) -> Result<f64, AlphaVantageErr> {
+    value
+        .parse()
+        .map_err(|_| AlphaVantageErr::DataInconsistency(format!("invalid {} '{}'", name, value)))
+}
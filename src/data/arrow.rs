@@ -0,0 +1,296 @@
+//! apache arrow conversion and ipc files for quotes, panels and returns
+use super::panel::PricePanel;
+use super::yahoo::QuoteItem;
+use crate::commons::date::{
+    date_to_epoch_days, datetime_to_micros, epoch_days_to_date, nanos_to_exchange_dt,
+    single_timezone, DateTimeError,
+};
+use ::arrow::array::{
+    Array, ArrayRef, Date32Array, Float64Array, RecordBatch, TimestampMicrosecondArray, UInt64Array,
+};
+use ::arrow::compute::concat_batches;
+use ::arrow::datatypes::{DataType, Field, Schema, TimeUnit};
+use ::arrow::ipc::{reader::FileReader, writer::FileWriter};
+use ndarray::{Array1, Array2};
+use std::{fs::File, path::Path, sync::Arc};
+use thiserror::Error;
+use time::{Date, OffsetDateTime};
+
+#[derive(Debug, Error)]
+pub enum ArrowError {
+    #[error("Arrow conversion failed: {0}")]
+    Arrow(#[from] ::arrow::error::ArrowError),
+    #[cfg(feature = "parquet")]
+    #[error("Parquet read or write failed: {0}")]
+    Parquet(#[from] ::parquet::errors::ParquetError),
+    #[error("Failed to access arrow file: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("Missing column '{0}' in record batch.")]
+    MissingColumn(String),
+    #[error("Column '{0}' has type {1}, expected {2}")]
+    InvalidColumnType(String, String, String),
+    #[error("Column '{0}' contains null values.")]
+    NullValue(String),
+    #[error("Invalid timestamp or date in column '{0}'")]
+    InvalidTimestamp(String),
+    #[error("Unknown timezone '{0}'")]
+    UnknownTimezone(String),
+    #[error("Failed to convert quote datetimes: {0}")]
+    DateTime(#[from] DateTimeError),
+}
+
+// trait to model conversion of a dataset into a single arrow record batch
+pub trait ToRecordBatch {
+    fn to_record_batch(&self) -> Result<RecordBatch, ArrowError>;
+}
+
+// trait to model conversion of an arrow record batch back into a dataset
+pub trait FromRecordBatch: Sized {
+    fn from_record_batch(batch: &RecordBatch) -> Result<Self, ArrowError>;
+}
+
+// single asset quotes: `datetime` timestamp (microseconds, exchange timezone) followed by
+// open, high, low, close, adjclose (float64) and volume (uint64)
+// the quotes timezone is stored in the schema, quotes in different timezones are rejected
+impl ToRecordBatch for Array1<QuoteItem> {
+    fn to_record_batch(&self) -> Result<RecordBatch, ArrowError> {
+        let timezone = single_timezone(self)?;
+        let price = |f: fn(&QuoteItem) -> f64| -> ArrayRef {
+            Arc::new(self.iter().map(f).collect::<Float64Array>())
+        };
+        let columns: Vec<ArrayRef> = vec![
+            Arc::new(
+                TimestampMicrosecondArray::from_iter_values(
+                    self.iter().map(|q| datetime_to_micros(q.datetime)),
+                )
+                .with_timezone(timezone),
+            ),
+            price(|q| q.open),
+            price(|q| q.high),
+            price(|q| q.low),
+            price(|q| q.close),
+            price(|q| q.adjclose),
+            Arc::new(self.iter().map(|q| q.volume).collect::<UInt64Array>()),
+        ];
+        Ok(RecordBatch::try_new(quotes_schema(timezone), columns)?)
+    }
+}
+
+impl FromRecordBatch for Array1<QuoteItem> {
+    fn from_record_batch(batch: &RecordBatch) -> Result<Self, ArrowError> {
+        let (datetime, timezone) = timestamp_column(batch, "datetime")?;
+        let open = f64_column(batch, "open")?;
+        let high = f64_column(batch, "high")?;
+        let low = f64_column(batch, "low")?;
+        let close = f64_column(batch, "close")?;
+        let adjclose = f64_column(batch, "adjclose")?;
+        let volume = column::<UInt64Array>(batch, "volume", "UInt64")?;
+        for (name, col) in [
+            ("datetime", datetime as &dyn Array),
+            ("open", open),
+            ("high", high),
+            ("low", low),
+            ("close", close),
+            ("adjclose", adjclose),
+            ("volume", volume),
+        ] {
+            if col.null_count() > 0 {
+                return Err(ArrowError::NullValue(name.to_string()));
+            }
+        }
+
+        (0..batch.num_rows())
+            .map(|i| {
+                Ok(QuoteItem {
+                    datetime: from_micros(datetime.value(i), &timezone, "datetime")?,
+                    timezone: timezone.clone(),
+                    open: open.value(i),
+                    high: high.value(i),
+                    low: low.value(i),
+                    close: close.value(i),
+                    adjclose: adjclose.value(i),
+                    volume: volume.value(i),
+                })
+            })
+            .collect()
+    }
+}
+
+// multi-asset panel: `date` column (date32) followed by one float64 column per ticker
+// missing prices (NaN) are written as nulls and read back as NaN
+impl ToRecordBatch for PricePanel {
+    fn to_record_batch(&self) -> Result<RecordBatch, ArrowError> {
+        let mut fields = vec![Field::new("date", DataType::Date32, false)];
+        fields.extend(
+            self.tickers
+                .iter()
+                .map(|t| Field::new(t, DataType::Float64, true)),
+        );
+
+        let mut columns: Vec<ArrayRef> = vec![Arc::new(Date32Array::from_iter_values(
+            self.dates.iter().map(|d| date_to_epoch_days(*d)),
+        ))];
+        columns.extend(self.values.columns().into_iter().map(|c| {
+            Arc::new(
+                c.iter()
+                    .map(|v| (!v.is_nan()).then_some(*v))
+                    .collect::<Float64Array>(),
+            ) as ArrayRef
+        }));
+        Ok(RecordBatch::try_new(
+            Arc::new(Schema::new(fields)),
+            columns,
+        )?)
+    }
+}
+
+impl FromRecordBatch for PricePanel {
+    fn from_record_batch(batch: &RecordBatch) -> Result<Self, ArrowError> {
+        let dates = column::<Date32Array>(batch, "date", "Date32")?;
+        if dates.null_count() > 0 {
+            return Err(ArrowError::NullValue("date".to_string()));
+        }
+        let dates = dates
+            .values()
+            .iter()
+            .map(|d| {
+                epoch_days_to_date(*d).map_err(|_| ArrowError::InvalidTimestamp("date".to_string()))
+            })
+            .collect::<Result<Vec<Date>, ArrowError>>()?;
+
+        let tickers: Vec<String> = batch
+            .schema()
+            .fields()
+            .iter()
+            .map(|f| f.name().clone())
+            .filter(|n| n != "date")
+            .collect();
+        let mut values = Array2::from_elem((dates.len(), tickers.len()), f64::NAN);
+        for (j, ticker) in tickers.iter().enumerate() {
+            for (i, v) in f64_column(batch, ticker)?.iter().enumerate() {
+                values[[i, j]] = v.unwrap_or(f64::NAN);
+            }
+        }
+        Ok(PricePanel {
+            dates,
+            tickers,
+            values,
+        })
+    }
+}
+
+// returns series: `datetime` timestamp (microseconds, utc) and `return` (float64)
+impl ToRecordBatch for Array1<(OffsetDateTime, f64)> {
+    fn to_record_batch(&self) -> Result<RecordBatch, ArrowError> {
+        let schema = Schema::new(vec![
+            Field::new(
+                "datetime",
+                DataType::Timestamp(TimeUnit::Microsecond, Some("UTC".into())),
+                false,
+            ),
+            Field::new("return", DataType::Float64, false),
+        ]);
+        let columns: Vec<ArrayRef> = vec![
+            Arc::new(
+                TimestampMicrosecondArray::from_iter_values(
+                    self.iter().map(|(d, _)| datetime_to_micros(*d)),
+                )
+                .with_timezone("UTC"),
+            ),
+            Arc::new(self.iter().map(|(_, r)| *r).collect::<Float64Array>()),
+        ];
+        Ok(RecordBatch::try_new(Arc::new(schema), columns)?)
+    }
+}
+
+impl FromRecordBatch for Array1<(OffsetDateTime, f64)> {
+    fn from_record_batch(batch: &RecordBatch) -> Result<Self, ArrowError> {
+        let (datetime, timezone) = timestamp_column(batch, "datetime")?;
+        let returns = f64_column(batch, "return")?;
+        if datetime.null_count() > 0 || returns.null_count() > 0 {
+            return Err(ArrowError::NullValue("datetime/return".to_string()));
+        }
+        (0..batch.num_rows())
+            .map(|i| {
+                Ok((
+                    from_micros(datetime.value(i), &timezone, "datetime")?,
+                    returns.value(i),
+                ))
+            })
+            .collect()
+    }
+}
+
+// write a dataset into an arrow ipc (feather v2) file
+pub fn write_ipc<P: AsRef<Path>, T: ToRecordBatch>(path: P, data: &T) -> Result<(), ArrowError> {
+    let batch = data.to_record_batch()?;
+    let mut writer = FileWriter::try_new(File::create(path)?, &batch.schema())?;
+    writer.write(&batch)?;
+    writer.finish()?;
+    Ok(())
+}
+
+// read a dataset from an arrow ipc (feather v2) file, every record batch in the file is concatenated
+pub fn read_ipc<P: AsRef<Path>, T: FromRecordBatch>(path: P) -> Result<T, ArrowError> {
+    let reader = FileReader::try_new(File::open(path)?, None)?;
+    let schema = reader.schema();
+    let batches = reader.collect::<Result<Vec<RecordBatch>, _>>()?;
+    T::from_record_batch(&concat_batches(&schema, &batches)?)
+}
+
+fn quotes_schema(timezone: &str) -> Arc<Schema> {
+    let price = |name: &str| Field::new(name, DataType::Float64, false);
+    Arc::new(Schema::new(vec![
+        Field::new(
+            "datetime",
+            DataType::Timestamp(TimeUnit::Microsecond, Some(timezone.into())),
+            false,
+        ),
+        price("open"),
+        price("high"),
+        price("low"),
+        price("close"),
+        price("adjclose"),
+        Field::new("volume", DataType::UInt64, false),
+    ]))
+}
+
+// helper function to get a typed column by name
+fn column<'a, T: Array + 'static>(
+    batch: &'a RecordBatch,
+    name: &str,
+    expected: &str,
+) -> Result<&'a T, ArrowError> {
+    let col = batch
+        .column_by_name(name)
+        .ok_or_else(|| ArrowError::MissingColumn(name.to_string()))?;
+    col.as_any().downcast_ref::<T>().ok_or_else(|| {
+        ArrowError::InvalidColumnType(
+            name.to_string(),
+            col.data_type().to_string(),
+            expected.to_string(),
+        )
+    })
+}
+
+fn f64_column<'a>(batch: &'a RecordBatch, name: &str) -> Result<&'a Float64Array, ArrowError> {
+    column::<Float64Array>(batch, name, "Float64")
+}
+
+// helper function to get a microsecond timestamp column and its timezone (utc when not set)
+fn timestamp_column<'a>(
+    batch: &'a RecordBatch,
+    name: &str,
+) -> Result<(&'a TimestampMicrosecondArray, String), ArrowError> {
+    let col = column::<TimestampMicrosecondArray>(batch, name, "Timestamp(Microsecond)")?;
+    let timezone = col.timezone().unwrap_or("UTC").to_string();
+    Ok((col, timezone))
+}
+
+// helper function to convert microseconds since epoch into datetime in the given IANA timezone
+fn from_micros(micros: i64, timezone: &str, column: &str) -> Result<OffsetDateTime, ArrowError> {
+    nanos_to_exchange_dt(micros as i128 * 1_000, timezone).map_err(|e| match e {
+        DateTimeError::UnknownTimezone(tz) => ArrowError::UnknownTimezone(tz),
+        _ => ArrowError::InvalidTimestamp(column.to_string()),
+    })
+}
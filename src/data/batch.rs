@@ -0,0 +1,156 @@
+//! concurrent multi-ticker fetching with retry and per-ticker error report
+use super::align::{Align, AlignPolicy, AlignReport};
+use super::provider::MarketDataProvider;
+use super::yahoo::{group_by_date, MultiQuoteItem, QuoteItem};
+use futures::future::join_all;
+use ndarray::Array1;
+use std::{future::Future, time::Duration};
+use tokio::sync::Semaphore;
+
+// struct to model batch fetching options
+// requests failing with a transient error are retried up to `max_retries` times,
+// waiting `backoff` before the first retry and doubling the wait at every following one
+#[derive(Debug, Clone)]
+pub struct BatchConfig {
+    pub concurrency: usize,
+    pub max_retries: u32,
+    pub backoff: Duration,
+}
+
+impl Default for BatchConfig {
+    fn default() -> Self {
+        Self {
+            concurrency: 8,
+            max_retries: 3,
+            backoff: Duration::from_millis(500),
+        }
+    }
+}
+
+// struct to model batch fetching outcome: successful series and the error of every failed ticker
+// both lists keep the order tickers were requested in
+#[derive(Debug)]
+pub struct BatchQuotes<E> {
+    pub quotes: Vec<(String, Array1<QuoteItem>)>,
+    pub errors: Vec<(String, E)>,
+}
+
+impl<E> BatchQuotes<E> {
+    // true when every ticker was fetched successfully
+    pub fn is_complete(&self) -> bool {
+        self.errors.is_empty()
+    }
+
+    // list of tickers that could not be fetched
+    pub fn failed_tickers(&self) -> Vec<&str> {
+        self.errors.iter().map(|(t, _)| t.as_str()).collect()
+    }
+
+    // group successful series by date into multiquotes
+    pub fn multi_quotes(&self) -> Array1<MultiQuoteItem> {
+        group_by_date(self.quotes.clone())
+    }
+
+    // group successful series by date and align them with a missing-data policy
+    pub fn aligned_quotes(&self, policy: AlignPolicy) -> (Array1<MultiQuoteItem>, AlignReport) {
+        self.multi_quotes().align(policy)
+    }
+}
+
+// fetch quotation data for multiple assets concurrently, a failing ticker does not abort the batch
+pub async fn get_quotes_batch<P: MarketDataProvider>(
+    provider: &P,
+    tickers: &[&str],
+    start_date: Option<&str>,
+    end_date: Option<&str>,
+    period: Option<&str>,
+    interval: Option<&str>,
+    config: &BatchConfig,
+) -> BatchQuotes<P::Error> {
+    let (quotes, errors) = batch_requests(
+        tickers,
+        config,
+        |e| provider.is_transient(e),
+        |t| provider.get_quotes(t, start_date, end_date, period, interval),
+    )
+    .await;
+    BatchQuotes { quotes, errors }
+}
+
+// run a request for every ticker concurrently, retrying transient failures of each one
+// a failing ticker does not abort the batch, successes and errors keep the requested order
+pub async fn batch_requests<'a, T, E, F, Fut>(
+    tickers: &[&'a str],
+    config: &BatchConfig,
+    is_transient: impl Fn(&E) -> bool,
+    request: F,
+) -> (Vec<(String, T)>, Vec<(String, E)>)
+where
+    F: Fn(&'a str) -> Fut,
+    Fut: Future<Output = Result<T, E>>,
+{
+    // every ticker waits for a permit, so at most `concurrency` requests are in flight
+    let permits = Semaphore::new(config.concurrency.max(1));
+    let requests: Vec<_> = tickers
+        .iter()
+        .map(|t| {
+            let (permits, is_transient, request) = (&permits, &is_transient, &request);
+            async move {
+                let _permit = permits.acquire().await;
+                let r = retry(config, is_transient, || request(t)).await;
+                (t.to_string(), r)
+            }
+        })
+        .collect();
+
+    let (mut successes, mut errors) = (Vec::new(), Vec::new());
+    for (ticker, r) in join_all(requests).await {
+        match r {
+            Ok(value) => successes.push((ticker, value)),
+            Err(e) => errors.push((ticker, e)),
+        }
+    }
+    (successes, errors)
+}
+
+// fetch quotation data for single asset, retrying transient failures with exponential backoff
+pub async fn get_quotes_with_retry<P: MarketDataProvider>(
+    provider: &P,
+    symbol: &str,
+    start_date: Option<&str>,
+    end_date: Option<&str>,
+    period: Option<&str>,
+    interval: Option<&str>,
+    config: &BatchConfig,
+) -> Result<Array1<QuoteItem>, P::Error> {
+    retry(
+        config,
+        |e| provider.is_transient(e),
+        || provider.get_quotes(symbol, start_date, end_date, period, interval),
+    )
+    .await
+}
+
+// run a request, retrying failures classified as transient with exponential backoff
+pub async fn retry<T, E, F, Fut>(
+    config: &BatchConfig,
+    is_transient: impl Fn(&E) -> bool,
+    mut request: F,
+) -> Result<T, E>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<T, E>>,
+{
+    let mut backoff = config.backoff;
+    let mut attempt = 0;
+    loop {
+        match request().await {
+            Err(e) if attempt < config.max_retries && is_transient(&e) => {
+                tokio::time::sleep(backoff).await;
+                backoff *= 2;
+                attempt += 1;
+            }
+            r => return r,
+        }
+    }
+}
@@ -0,0 +1,302 @@
+//! local on-disk quote cache
+use super::provider::MarketDataProvider;
+use super::yahoo::{group_by_date, MultiQuoteItem, OptionContract, OptionType, QuoteItem};
+use crate::commons::date::{parse_date, DateTimeError, DateType};
+use ndarray::Array1;
+use serde::{Deserialize, Serialize};
+use std::{collections::HashSet, error::Error, fs, path::PathBuf};
+use thiserror::Error;
+use time::{Date, OffsetDateTime};
+
+#[derive(Debug, Error)]
+pub enum CacheError {
+    #[error("Market data provider request failed: {0}")]
+    Provider(#[source] Box<dyn Error + Send + Sync>),
+    #[error("Failed to access quote cache: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("Failed to (de)serialize quote cache entry: {0}")]
+    Serialization(#[from] serde_json::Error),
+    #[error("Invalid date format: {0}")]
+    InvalidDateFormat(#[from] DateTimeError),
+    #[error("Offline mode: no cached data for {0}")]
+    NotCached(String),
+}
+
+// struct to model a cached segment: quotes plus the date range they cover (inclusive)
+// a cache file holds the disjoint segments of a symbol and interval sorted by start date
+#[derive(Debug, Serialize, Deserialize)]
+struct CacheEntry {
+    start: Date,
+    end: Date,
+    quotes: Vec<QuoteItem>,
+}
+
+// struct to model a caching layer on top of any market data provider
+// only date range requests are cached, one file per symbol and interval is stored in `dir`
+pub struct QuoteCache<P: MarketDataProvider> {
+    pub provider: P,
+    pub dir: PathBuf,
+    pub offline: bool,
+}
+
+impl<P: MarketDataProvider> QuoteCache<P> {
+    // create new cache instance storing entries in `dir`
+    pub fn new(provider: P, dir: impl Into<PathBuf>) -> Self {
+        Self {
+            provider,
+            dir: dir.into(),
+            offline: false,
+        }
+    }
+
+    // set offline-only mode: requests are served from disk and never reach the provider
+    pub fn offline(mut self, offline: bool) -> Self {
+        self.offline = offline;
+        self
+    }
+
+    // remove cached series for a symbol and interval
+    pub fn invalidate(&self, symbol: &str, interval: &str) -> Result<(), CacheError> {
+        let path = self.entry_path(symbol, interval);
+        if path.exists() {
+            fs::remove_file(path)?;
+        }
+        Ok(())
+    }
+
+    // get quotation data for a date range, fetching from the provider only what is not cached
+    pub async fn get_cached_quotes(
+        &self,
+        symbol: &str,
+        start_date: &str,
+        end_date: &str,
+        interval: &str,
+    ) -> Result<Array1<QuoteItem>, CacheError> {
+        // validate dates before touching the cache
+        let start = parse_date(start_date, DateType::Start)?.date();
+        let end = parse_date(end_date, DateType::End)?.date();
+
+        let segments = self.read_segments(symbol, interval)?;
+        if let Some(s) = segments.iter().find(|s| s.start <= start && end <= s.end) {
+            return Ok(slice(&s.quotes, start, end));
+        }
+
+        if self.offline {
+            return Err(CacheError::NotCached(format!(
+                "{} ({}) from {} to {}",
+                symbol, interval, start_date, end_date
+            )));
+        }
+
+        // segments overlapping the request are merged with it, fetching only the gaps between them;
+        // a request not overlapping any segment fetches just its own range and is kept apart,
+        // so the provider is never asked for a span wider than the request
+        let (overlapping, mut segments): (Vec<CacheEntry>, Vec<CacheEntry>) = segments
+            .into_iter()
+            .partition(|s| s.start <= end && start <= s.end);
+        let mut gaps = Vec::new();
+        if overlapping.is_empty() {
+            gaps.push((start, end));
+        } else {
+            let mut covered = start;
+            for s in &overlapping {
+                if covered < s.start {
+                    gaps.push((covered, s.start));
+                }
+                covered = covered.max(s.end);
+            }
+            if covered < end {
+                gaps.push((covered, end));
+            }
+        }
+
+        let mut entry = CacheEntry {
+            start: overlapping.first().map_or(start, |s| s.start.min(start)),
+            end: overlapping.last().map_or(end, |s| s.end.max(end)),
+            quotes: Vec::new(),
+        };
+        for s in overlapping {
+            entry.quotes = merge(entry.quotes, s.quotes);
+        }
+        for (gap_start, gap_end) in gaps {
+            let fetched = self.fetch(symbol, gap_start, gap_end, interval).await?;
+            entry.quotes = merge(entry.quotes, fetched);
+        }
+        let quotes = slice(&entry.quotes, start, end);
+
+        // only fully closed days are marked as covered: today's session may still be running,
+        // so today (and any future date) is fetched again on the next request
+        let last_closed = OffsetDateTime::now_utc()
+            .date()
+            .previous_day()
+            .unwrap_or(Date::MIN);
+        entry.end = entry.end.min(last_closed);
+
+        // a segment made only of days not closed yet covers nothing and is not stored
+        if entry.start <= entry.end {
+            segments.push(entry);
+            segments.sort_by_key(|s| s.start);
+            self.write_segments(symbol, interval, &segments)?;
+        }
+        Ok(quotes)
+    }
+
+    async fn fetch(
+        &self,
+        symbol: &str,
+        start: Date,
+        end: Date,
+        interval: &str,
+    ) -> Result<Vec<QuoteItem>, CacheError> {
+        Ok(self
+            .provider
+            .get_quotes(
+                symbol,
+                Some(&start.to_string()),
+                Some(&end.to_string()),
+                None,
+                Some(interval),
+            )
+            .await
+            .map_err(|e| CacheError::Provider(Box::new(e)))?
+            .to_vec())
+    }
+
+    fn entry_path(&self, symbol: &str, interval: &str) -> PathBuf {
+        let key: String = format!("{}_{}", symbol, interval)
+            .chars()
+            .map(|c| {
+                if c.is_ascii_alphanumeric() || c == '.' || c == '-' || c == '_' {
+                    c
+                } else {
+                    '_'
+                }
+            })
+            .collect();
+        self.dir.join(format!("{}.json", key))
+    }
+
+    fn read_segments(&self, symbol: &str, interval: &str) -> Result<Vec<CacheEntry>, CacheError> {
+        let path = self.entry_path(symbol, interval);
+        if !path.exists() {
+            return Ok(Vec::new());
+        }
+        Ok(serde_json::from_str(&fs::read_to_string(path)?)?)
+    }
+
+    // write into a temporary file first so an interrupted run never leaves a corrupted entry
+    fn write_segments(
+        &self,
+        symbol: &str,
+        interval: &str,
+        segments: &[CacheEntry],
+    ) -> Result<(), CacheError> {
+        fs::create_dir_all(&self.dir)?;
+        let path = self.entry_path(symbol, interval);
+        let tmp = path.with_extension("json.tmp");
+        fs::write(&tmp, serde_json::to_string(segments)?)?;
+        fs::rename(tmp, path)?;
+        Ok(())
+    }
+}
+
+// cached quotes as market data provider, requests without a date range are passed through
+impl<P: MarketDataProvider> MarketDataProvider for QuoteCache<P> {
+    type Error = CacheError;
+
+    async fn get_quotes(
+        &self,
+        symbol: &str,
+        start_date: Option<&str>,
+        end_date: Option<&str>,
+        period: Option<&str>,
+        interval: Option<&str>,
+    ) -> Result<Array1<QuoteItem>, CacheError> {
+        if let (Some(start_date), Some(end_date)) = (start_date, end_date) {
+            return self
+                .get_cached_quotes(symbol, start_date, end_date, interval.unwrap_or("1m"))
+                .await;
+        }
+
+        if self.offline {
+            return Err(CacheError::NotCached(symbol.to_string()));
+        }
+        self.provider
+            .get_quotes(symbol, start_date, end_date, period, interval)
+            .await
+            .map_err(|e| CacheError::Provider(Box::new(e)))
+    }
+
+    async fn get_multiple_quotes(
+        &self,
+        tickers: Vec<&str>,
+        start_date: Option<&str>,
+        end_date: Option<&str>,
+        period: Option<&str>,
+        interval: Option<&str>,
+    ) -> Result<Array1<MultiQuoteItem>, CacheError> {
+        let mut r: Vec<(String, Array1<QuoteItem>)> = Vec::new();
+        for t in tickers {
+            r.push((
+                t.to_string(),
+                self.get_quotes(t, start_date, end_date, period, interval)
+                    .await?,
+            ));
+        }
+        Ok(group_by_date(r))
+    }
+
+    async fn get_latest_quote(&self, ticker: &str) -> Result<f64, CacheError> {
+        if self.offline {
+            return Err(CacheError::NotCached(ticker.to_string()));
+        }
+        self.provider
+            .get_latest_quote(ticker)
+            .await
+            .map_err(|e| CacheError::Provider(Box::new(e)))
+    }
+
+    async fn get_options(
+        &self,
+        ticker: &str,
+        option_type: OptionType,
+    ) -> Result<Array1<OptionContract>, CacheError> {
+        if self.offline {
+            return Err(CacheError::NotCached(ticker.to_string()));
+        }
+        self.provider
+            .get_options(ticker, option_type)
+            .await
+            .map_err(|e| CacheError::Provider(Box::new(e)))
+    }
+
+    fn is_transient(&self, error: &CacheError) -> bool {
+        match error {
+            CacheError::Provider(e) => e
+                .downcast_ref::<P::Error>()
+                .is_some_and(|e| self.provider.is_transient(e)),
+            _ => false,
+        }
+    }
+}
+
+// helper function to merge two quote series, newer fetched bars replace cached ones with same datetime
+fn merge(cached: Vec<QuoteItem>, fetched: Vec<QuoteItem>) -> Vec<QuoteItem> {
+    let fetched_dt: HashSet<OffsetDateTime> = fetched.iter().map(|f| f.datetime).collect();
+    let mut quotes: Vec<QuoteItem> = cached
+        .into_iter()
+        .filter(|c| !fetched_dt.contains(&c.datetime))
+        .chain(fetched)
+        .collect();
+    quotes.sort_by_key(|q| q.datetime);
+    quotes
+}
+
+// helper function to select quotes whose exchange local date falls into [start, end]
+fn slice(quotes: &[QuoteItem], start: Date, end: Date) -> Array1<QuoteItem> {
+    quotes
+        .iter()
+        .filter(|q| start <= q.datetime.date() && q.datetime.date() <= end)
+        .cloned()
+        .collect()
+}
@@ -0,0 +1,365 @@
+//! csv loader and writer for quotation data
+use super::yahoo::{MultiQuoteItem, QuoteItem};
+use crate::commons::date::{local_to_exchange_dt, DateTimeError};
+use ::csv::{ReaderBuilder, StringRecord, WriterBuilder};
+use ndarray::Array1;
+use std::{
+    collections::{BTreeSet, HashMap},
+    error::Error,
+    fs::File,
+    io::{Read, Write},
+    path::Path,
+};
+use thiserror::Error;
+use time::{
+    format_description::{self, BorrowedFormatItem},
+    Date, OffsetDateTime, PrimitiveDateTime, Time,
+};
+
+#[derive(Debug, Error)]
+pub enum CsvError {
+    #[error("Failed to access csv file: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("Failed to read or write csv data: {0}")]
+    Csv(#[from] ::csv::Error),
+    #[error("Invalid date format description '{0}': {1}")]
+    InvalidDateFormat(String, #[source] Box<dyn Error + Send + Sync>),
+    #[error("Failed to format date '{0}': {1}")]
+    FormatFailed(String, #[source] time::error::Format),
+    #[error("Unknown timezone '{0}'")]
+    UnknownTimezone(String),
+    #[error("Missing column '{0}' in csv header.")]
+    MissingColumn(String),
+    #[error("Malformed row {row}: column '{column}' has invalid value '{value}'")]
+    MalformedRow {
+        row: usize,
+        column: String,
+        value: String,
+        #[source]
+        source: Box<dyn Error + Send + Sync>,
+    },
+    #[error("Csv file contains no data rows.")]
+    EmptyDataSet,
+}
+
+// struct to model the mapping between csv header names and quote fields
+#[derive(Debug, Clone)]
+pub struct CsvColumns {
+    pub datetime: String,
+    pub open: String,
+    pub high: String,
+    pub low: String,
+    pub close: String,
+    pub adjclose: String,
+    pub volume: String,
+}
+
+// default mapping follows yahoo! finance csv export headers
+impl Default for CsvColumns {
+    fn default() -> Self {
+        Self {
+            datetime: "Date".to_string(),
+            open: "Open".to_string(),
+            high: "High".to_string(),
+            low: "Low".to_string(),
+            close: "Close".to_string(),
+            adjclose: "Adj Close".to_string(),
+            volume: "Volume".to_string(),
+        }
+    }
+}
+
+// struct to model csv reading and writing options
+// `date_format` is a `time` format description (e.g. "[year]-[month]-[day]" or "[day]/[month]/[year]")
+// `timezone` is the IANA timezone the csv dates are expressed in (e.g. "Europe/Rome")
+#[derive(Debug, Clone)]
+pub struct CsvConfig {
+    pub delimiter: u8,
+    pub date_format: String,
+    pub timezone: String,
+    pub columns: CsvColumns,
+}
+
+impl Default for CsvConfig {
+    fn default() -> Self {
+        Self {
+            delimiter: b',',
+            date_format: "[year]-[month]-[day]".to_string(),
+            timezone: "UTC".to_string(),
+            columns: CsvColumns::default(),
+        }
+    }
+}
+
+// read single asset OHLCV csv file into an array of quotes
+pub fn read_quotes<P: AsRef<Path>>(
+    path: P,
+    config: &CsvConfig,
+) -> Result<Array1<QuoteItem>, CsvError> {
+    read_quotes_from_reader(File::open(path)?, config)
+}
+
+// read single asset OHLCV csv data from any reader into an array of quotes
+// if the adjusted close column is missing close is used instead, if the volume column is missing volume is set to 0
+pub fn read_quotes_from_reader<R: Read>(
+    reader: R,
+    config: &CsvConfig,
+) -> Result<Array1<QuoteItem>, CsvError> {
+    let fmt = parse_format(&config.date_format)?;
+    let mut rdr = ReaderBuilder::new()
+        .delimiter(config.delimiter)
+        .trim(::csv::Trim::All)
+        .from_reader(reader);
+    let headers = rdr.headers()?.clone();
+
+    let cols = &config.columns;
+    let datetime_idx = column_index(&headers, &cols.datetime)?;
+    let open_idx = column_index(&headers, &cols.open)?;
+    let high_idx = column_index(&headers, &cols.high)?;
+    let low_idx = column_index(&headers, &cols.low)?;
+    let close_idx = column_index(&headers, &cols.close)?;
+    let adjclose_idx = column_index(&headers, &cols.adjclose).ok();
+    let volume_idx = column_index(&headers, &cols.volume).ok();
+
+    let mut quotes: Vec<QuoteItem> = Vec::new();
+    for (i, record) in rdr.records().enumerate() {
+        let record = record?;
+        // header is row 1, first data row is row 2
+        let row = i + 2;
+
+        let close = parse_field::<f64>(&record, close_idx, &cols.close, row)?;
+        let datetime = parse_datetime(&record, datetime_idx, &cols.datetime, row, &fmt)?;
+        quotes.push(QuoteItem {
+            datetime: local_to_exchange_dt(datetime, &config.timezone).map_err(|e| match e {
+                DateTimeError::UnknownTimezone(tz) => CsvError::UnknownTimezone(tz),
+                e => CsvError::MalformedRow {
+                    row,
+                    column: cols.datetime.clone(),
+                    value: record.get(datetime_idx).unwrap_or_default().to_string(),
+                    source: e.into(),
+                },
+            })?,
+            timezone: config.timezone.clone(),
+            open: parse_field(&record, open_idx, &cols.open, row)?,
+            high: parse_field(&record, high_idx, &cols.high, row)?,
+            low: parse_field(&record, low_idx, &cols.low, row)?,
+            close,
+            adjclose: match adjclose_idx {
+                Some(idx) => parse_field(&record, idx, &cols.adjclose, row)?,
+                None => close,
+            },
+            volume: match volume_idx {
+                Some(idx) => parse_field(&record, idx, &cols.volume, row)?,
+                None => 0,
+            },
+        });
+    }
+
+    if quotes.is_empty() {
+        return Err(CsvError::EmptyDataSet);
+    }
+
+    quotes.sort_by_key(|q| q.datetime);
+    Ok(Array1::from_vec(quotes))
+}
+
+// read wide multi-ticker csv file (date column followed by one column per ticker) into an array of multiquotes
+pub fn read_multi_quotes<P: AsRef<Path>>(
+    path: P,
+    config: &CsvConfig,
+) -> Result<Array1<MultiQuoteItem>, CsvError> {
+    read_multi_quotes_from_reader(File::open(path)?, config)
+}
+
+// read wide multi-ticker csv data from any reader into an array of multiquotes
+// empty cells are treated as missing prices and the ticker is left out of that date
+pub fn read_multi_quotes_from_reader<R: Read>(
+    reader: R,
+    config: &CsvConfig,
+) -> Result<Array1<MultiQuoteItem>, CsvError> {
+    let fmt = parse_format(&config.date_format)?;
+    let mut rdr = ReaderBuilder::new()
+        .delimiter(config.delimiter)
+        .trim(::csv::Trim::All)
+        .from_reader(reader);
+    let headers = rdr.headers()?.clone();
+    let date_idx = column_index(&headers, &config.columns.datetime)?;
+
+    let mut m_quotes: Vec<MultiQuoteItem> = Vec::new();
+    for (i, record) in rdr.records().enumerate() {
+        let record = record?;
+        let row = i + 2;
+
+        let date = parse_datetime(&record, date_idx, &config.columns.datetime, row, &fmt)?.date();
+        let mut prices = HashMap::new();
+        for (idx, ticker) in headers.iter().enumerate() {
+            if idx == date_idx || record.get(idx).is_none_or(str::is_empty) {
+                continue;
+            }
+            prices.insert(ticker.to_string(), parse_field(&record, idx, ticker, row)?);
+        }
+        m_quotes.push(MultiQuoteItem { date, prices });
+    }
+
+    if m_quotes.is_empty() {
+        return Err(CsvError::EmptyDataSet);
+    }
+
+    m_quotes.sort_by_key(|q| q.date);
+    Ok(Array1::from_vec(m_quotes))
+}
+
+// write an array of quotes into a single asset OHLCV csv file
+pub fn write_quotes<P: AsRef<Path>>(
+    path: P,
+    quotes: &Array1<QuoteItem>,
+    config: &CsvConfig,
+) -> Result<(), CsvError> {
+    write_quotes_to_writer(File::create(path)?, quotes, config)
+}
+
+// write an array of quotes as single asset OHLCV csv data into any writer
+pub fn write_quotes_to_writer<W: Write>(
+    writer: W,
+    quotes: &Array1<QuoteItem>,
+    config: &CsvConfig,
+) -> Result<(), CsvError> {
+    let fmt = parse_format(&config.date_format)?;
+    let mut wtr = WriterBuilder::new()
+        .delimiter(config.delimiter)
+        .from_writer(writer);
+
+    let cols = &config.columns;
+    wtr.write_record([
+        &cols.datetime,
+        &cols.open,
+        &cols.high,
+        &cols.low,
+        &cols.close,
+        &cols.adjclose,
+        &cols.volume,
+    ])?;
+    for q in quotes {
+        wtr.write_record([
+            format_datetime(q.datetime, &fmt)?,
+            q.open.to_string(),
+            q.high.to_string(),
+            q.low.to_string(),
+            q.close.to_string(),
+            q.adjclose.to_string(),
+            q.volume.to_string(),
+        ])?;
+    }
+    wtr.flush()?;
+    Ok(())
+}
+
+// write an array of multiquotes into a wide multi-ticker csv file
+pub fn write_multi_quotes<P: AsRef<Path>>(
+    path: P,
+    quotes: &Array1<MultiQuoteItem>,
+    config: &CsvConfig,
+) -> Result<(), CsvError> {
+    write_multi_quotes_to_writer(File::create(path)?, quotes, config)
+}
+
+// write an array of multiquotes as wide multi-ticker csv data into any writer
+// tickers are written in alphabetical order, missing prices are written as empty cells
+pub fn write_multi_quotes_to_writer<W: Write>(
+    writer: W,
+    quotes: &Array1<MultiQuoteItem>,
+    config: &CsvConfig,
+) -> Result<(), CsvError> {
+    let fmt = parse_format(&config.date_format)?;
+    let mut wtr = WriterBuilder::new()
+        .delimiter(config.delimiter)
+        .from_writer(writer);
+
+    let tickers: BTreeSet<&String> = quotes.iter().flat_map(|q| q.prices.keys()).collect();
+
+    let mut header = vec![config.columns.datetime.clone()];
+    header.extend(tickers.iter().map(|t| t.to_string()));
+    wtr.write_record(&header)?;
+
+    for q in quotes {
+        let mut record = vec![format_date(q.date, &fmt)?];
+        record.extend(
+            tickers
+                .iter()
+                .map(|t| q.prices.get(*t).map(f64::to_string).unwrap_or_default()),
+        );
+        wtr.write_record(&record)?;
+    }
+    wtr.flush()?;
+    Ok(())
+}
+
+// helper function to parse a runtime date format description
+fn parse_format(date_format: &str) -> Result<Vec<BorrowedFormatItem<'_>>, CsvError> {
+    format_description::parse(date_format)
+        .map_err(|err| CsvError::InvalidDateFormat(date_format.to_string(), err.into()))
+}
+
+// helper function to find a column position in csv header
+fn column_index(headers: &StringRecord, name: &str) -> Result<usize, CsvError> {
+    headers
+        .iter()
+        .position(|h| h == name)
+        .ok_or_else(|| CsvError::MissingColumn(name.to_string()))
+}
+
+// helper function to parse a numeric csv field
+fn parse_field<T>(
+    record: &StringRecord,
+    idx: usize,
+    column: &str,
+    row: usize,
+) -> Result<T, CsvError>
+where
+    T: std::str::FromStr,
+    T::Err: Error + Send + Sync + 'static,
+{
+    let value = record.get(idx).unwrap_or_default();
+    value.parse::<T>().map_err(|err| CsvError::MalformedRow {
+        row,
+        column: column.to_string(),
+        value: value.to_string(),
+        source: err.into(),
+    })
+}
+
+// helper function to parse a csv date field, dates without time component are set to midnight
+fn parse_datetime(
+    record: &StringRecord,
+    idx: usize,
+    column: &str,
+    row: usize,
+    fmt: &[BorrowedFormatItem<'_>],
+) -> Result<PrimitiveDateTime, CsvError> {
+    let value = record.get(idx).unwrap_or_default();
+    PrimitiveDateTime::parse(value, fmt)
+        .or_else(|_| Date::parse(value, fmt).map(|d| PrimitiveDateTime::new(d, Time::MIDNIGHT)))
+        .map_err(|err| CsvError::MalformedRow {
+            row,
+            column: column.to_string(),
+            value: value.to_string(),
+            source: err.into(),
+        })
+}
+
+// helper function to format a quote datetime (in its own offset) with the csv date format
+fn format_datetime(
+    value: OffsetDateTime,
+    fmt: &[BorrowedFormatItem<'_>],
+) -> Result<String, CsvError> {
+    value
+        .format(fmt)
+        .map_err(|err| CsvError::FormatFailed(value.to_string(), err))
+}
+
+// helper function to format a multiquote date with the csv date format
+fn format_date(value: Date, fmt: &[BorrowedFormatItem<'_>]) -> Result<String, CsvError> {
+    PrimitiveDateTime::new(value, Time::MIDNIGHT)
+        .format(fmt)
+        .map_err(|err| CsvError::FormatFailed(value.to_string(), err))
+}
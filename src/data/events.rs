@@ -0,0 +1,59 @@
+//! corporate events: dividends and splits
+use super::yahoo::QuoteItem;
+use ndarray::Array1;
+use time::Date;
+
+// struct to model a cash dividend paid per share, `date` is the ex-dividend date
+#[derive(Debug, Clone, PartialEq)]
+pub struct DividendItem {
+    pub date: Date,
+    pub amount: f64,
+}
+
+// struct to model a stock split (e.g. 4:1 split has numerator 4 and denominator 1)
+#[derive(Debug, Clone, PartialEq)]
+pub struct SplitItem {
+    pub date: Date,
+    pub numerator: f64,
+    pub denominator: f64,
+}
+
+impl SplitItem {
+    // number of new shares for each old share
+    pub fn ratio(&self) -> f64 {
+        self.numerator / self.denominator
+    }
+}
+
+// rebuild split and dividend adjusted close series from quotes close prices (sorted by datetime)
+// prices before a split are divided by the split ratio, prices before an ex-dividend date are
+// multiplied by (1 - dividend / previous close). Events falling on non-trading days apply from the
+// next available quote. Yahoo! finance `close` is already split adjusted, pass no splits to
+// rebuild its `adjclose`
+pub fn adjusted_close(
+    quotes: &Array1<QuoteItem>,
+    dividends: &Array1<DividendItem>,
+    splits: &Array1<SplitItem>,
+) -> Array1<f64> {
+    let mut adj = Array1::zeros(quotes.len());
+    let mut factor = 1.0;
+
+    for i in (0..quotes.len()).rev() {
+        adj[i] = quotes[i].close * factor;
+
+        // events between previous and current quote only affect earlier prices
+        if i == 0 {
+            break;
+        }
+        let (prev, curr) = (quotes[i - 1].datetime.date(), quotes[i].datetime.date());
+        let in_range = |d: Date| prev < d && d <= curr;
+
+        for s in splits.iter().filter(|s| in_range(s.date)) {
+            factor /= s.ratio();
+        }
+        for d in dividends.iter().filter(|d| in_range(d.date)) {
+            factor *= 1.0 - d.amount / quotes[i - 1].close;
+        }
+    }
+    adj
+}
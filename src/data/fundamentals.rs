@@ -0,0 +1,141 @@
+//! company fundamentals and quote summary
+use serde::{Deserialize, Serialize};
+
+// struct to model company metadata used for screening, fields missing from the source are `None`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct QuoteSummary {
+    pub symbol: String,
+    pub name: Option<String>,
+    pub currency: Option<String>,
+    pub exchange: Option<String>,
+    pub sector: Option<String>,
+    pub industry: Option<String>,
+    pub market_cap: Option<f64>,
+    pub trailing_pe: Option<f64>,
+    pub forward_pe: Option<f64>,
+    pub trailing_eps: Option<f64>,
+    pub forward_eps: Option<f64>,
+    pub beta: Option<f64>,
+    pub shares_outstanding: Option<f64>,
+}
+
+// struct to model batch quote summary outcome, both lists keep the order tickers were requested in
+#[derive(Debug)]
+pub struct BatchSummaries<E> {
+    pub summaries: Vec<QuoteSummary>,
+    pub errors: Vec<(String, E)>,
+}
+
+// yahoo! finance quote summary endpoint response models
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct YSummaryResponse {
+    pub(crate) quote_summary: YSummaryResult,
+}
+
+#[derive(Deserialize)]
+pub(crate) struct YSummaryResult {
+    pub(crate) result: Option<Vec<YSummaryData>>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct YSummaryData {
+    price: Option<YPrice>,
+    summary_detail: Option<YSummaryDetail>,
+    default_key_statistics: Option<YKeyStatistics>,
+    asset_profile: Option<YAssetProfile>,
+}
+
+// numeric fields come as {"raw": 1.0, "fmt": "1.00"}, or {} when not available
+#[derive(Deserialize)]
+struct YValue {
+    raw: Option<f64>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct YPrice {
+    short_name: Option<String>,
+    long_name: Option<String>,
+    currency: Option<String>,
+    exchange_name: Option<String>,
+    market_cap: Option<YValue>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct YSummaryDetail {
+    trailing_p_e: Option<YValue>,
+    forward_p_e: Option<YValue>,
+    beta: Option<YValue>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct YKeyStatistics {
+    trailing_eps: Option<YValue>,
+    forward_eps: Option<YValue>,
+    shares_outstanding: Option<YValue>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct YAssetProfile {
+    sector: Option<String>,
+    industry: Option<String>,
+}
+
+impl YSummaryData {
+    // convert yahoo! finance modules into a flat quote summary
+    pub(crate) fn into_summary(self, symbol: &str) -> QuoteSummary {
+        let raw = |v: Option<YValue>| v.and_then(|v| v.raw);
+        let (price, detail, stats, profile) = (
+            self.price,
+            self.summary_detail,
+            self.default_key_statistics,
+            self.asset_profile,
+        );
+        let (name, currency, exchange, market_cap) = match price {
+            Some(p) => (
+                p.long_name.or(p.short_name),
+                p.currency,
+                p.exchange_name,
+                raw(p.market_cap),
+            ),
+            None => (None, None, None, None),
+        };
+        let (trailing_pe, forward_pe, beta) = match detail {
+            Some(d) => (raw(d.trailing_p_e), raw(d.forward_p_e), raw(d.beta)),
+            None => (None, None, None),
+        };
+        let (trailing_eps, forward_eps, shares_outstanding) = match stats {
+            Some(s) => (
+                raw(s.trailing_eps),
+                raw(s.forward_eps),
+                raw(s.shares_outstanding),
+            ),
+            None => (None, None, None),
+        };
+        let (sector, industry) = match profile {
+            Some(p) => (p.sector, p.industry),
+            None => (None, None),
+        };
+
+        QuoteSummary {
+            symbol: symbol.to_string(),
+            name,
+            currency,
+            exchange,
+            sector,
+            industry,
+            market_cap,
+            trailing_pe,
+            forward_pe,
+            trailing_eps,
+            forward_eps,
+            beta,
+            shares_outstanding,
+        }
+    }
+}
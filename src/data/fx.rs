@@ -0,0 +1,228 @@
+//! fx rates and currency conversion
+use super::provider::MarketDataProvider;
+use super::yahoo::QuoteItem;
+use crate::commons::date::DateTimeError;
+use ndarray::Array1;
+use std::{
+    collections::{BTreeMap, HashMap},
+    error::Error,
+};
+use thiserror::Error;
+use time::{Date, Duration, OffsetDateTime};
+
+// default maximum age of the rate used for a date without its own rate (weekends, holidays)
+const FX_MAX_LOOKBACK_DAYS: i64 = 7;
+
+#[derive(Debug, Error)]
+pub enum FxError {
+    #[error("Market data provider request failed: {0}")]
+    Provider(#[source] Box<dyn Error + Send + Sync>),
+    #[error("No fx rate available from {0} to {1}")]
+    MissingRate(String, String),
+    #[error("Fx rate from {0} to {1} on {2} is older than the maximum lookback")]
+    StaleRate(String, String, Date),
+    #[error("Invalid date format: {0}")]
+    InvalidDateFormat(#[from] DateTimeError),
+}
+
+// yahoo! finance symbol of a currency pair, quoted as units of `to` per unit of `from` (e.g. EURUSD=X)
+pub fn fx_symbol(from: &str, to: &str) -> String {
+    format!("{}{}=X", from.to_uppercase(), to.to_uppercase())
+}
+
+// struct to model a table of daily fx rates, `pivot` is the currency every fetched rate is quoted in
+// rates are keyed by (from, to) currency pair: 1 unit of `from` is worth `rate` units of `to`
+// dated lookups fall back to rates at most `max_lookback` older than the requested date
+#[derive(Debug, Clone)]
+pub struct FxRates {
+    pub pivot: String,
+    pub max_lookback: Duration,
+    rates: HashMap<(String, String), BTreeMap<Date, f64>>,
+}
+
+impl FxRates {
+    // create empty rate table quoting fetched rates against `pivot` (e.g. "USD")
+    pub fn new(pivot: &str) -> Self {
+        Self {
+            pivot: pivot.to_uppercase(),
+            max_lookback: Duration::days(FX_MAX_LOOKBACK_DAYS),
+            rates: HashMap::new(),
+        }
+    }
+
+    // set how old the last known rate may be when a date has no rate of its own
+    // negative lookbacks are clamped to zero: only rates of the date itself are used
+    pub fn with_max_lookback(mut self, max_lookback: Duration) -> Self {
+        self.max_lookback = max_lookback.max(Duration::ZERO);
+        self
+    }
+
+    // add or replace the rate of a currency pair on a date
+    pub fn insert(&mut self, from: &str, to: &str, date: Date, rate: f64) {
+        self.rates
+            .entry((from.to_uppercase(), to.to_uppercase()))
+            .or_default()
+            .insert(date, rate);
+    }
+
+    // fetch daily rates of every currency against the pivot for a date range
+    pub async fn fetch<P: MarketDataProvider>(
+        provider: &P,
+        currencies: &[&str],
+        pivot: &str,
+        start_date: &str,
+        end_date: &str,
+    ) -> Result<Self, FxError> {
+        let mut rates = Self::new(pivot);
+        let pivot = rates.pivot.clone();
+        for c in rates.foreign(currencies) {
+            let quotes = provider
+                .get_quotes(
+                    &fx_symbol(&c, &pivot),
+                    Some(start_date),
+                    Some(end_date),
+                    None,
+                    Some("1d"),
+                )
+                .await
+                .map_err(|e| FxError::Provider(Box::new(e)))?;
+            for q in quotes {
+                rates.insert(&c, &pivot, q.datetime.date(), q.close);
+            }
+        }
+        Ok(rates)
+    }
+
+    // fetch latest rate of every currency against the pivot, rates are stored on the current utc date
+    pub async fn fetch_latest<P: MarketDataProvider>(
+        provider: &P,
+        currencies: &[&str],
+        pivot: &str,
+    ) -> Result<Self, FxError> {
+        let mut rates = Self::new(pivot);
+        let pivot = rates.pivot.clone();
+        let today = OffsetDateTime::now_utc().date();
+        for c in rates.foreign(currencies) {
+            let rate = provider
+                .get_latest_quote(&fx_symbol(&c, &pivot))
+                .await
+                .map_err(|e| FxError::Provider(Box::new(e)))?;
+            rates.insert(&c, &pivot, today, rate);
+        }
+        Ok(rates)
+    }
+
+    // rate to convert `from` into `to` on a date, the last rate on or before the date is used
+    // as long as it is not older than `max_lookback`, otherwise a stale rate error is returned
+    // missing pairs are derived from the inverse pair or triangulated through a common currency
+    pub fn rate(&self, from: &str, to: &str, date: Date) -> Result<f64, FxError> {
+        self.lookup(from, to, Some(date))
+    }
+
+    // most recent rate to convert `from` into `to`
+    pub fn latest_rate(&self, from: &str, to: &str) -> Result<f64, FxError> {
+        self.lookup(from, to, None)
+    }
+
+    // convert an amount from a currency into another on a date
+    pub fn convert(&self, amount: f64, from: &str, to: &str, date: Date) -> Result<f64, FxError> {
+        Ok(amount * self.rate(from, to, date)?)
+    }
+
+    // convert quote prices into another currency, each bar at the rate of its exchange local date
+    pub fn convert_quotes(
+        &self,
+        quotes: &Array1<QuoteItem>,
+        from: &str,
+        to: &str,
+    ) -> Result<Array1<QuoteItem>, FxError> {
+        quotes
+            .iter()
+            .map(|q| {
+                let rate = self.rate(from, to, q.datetime.date())?;
+                Ok(QuoteItem {
+                    open: q.open * rate,
+                    high: q.high * rate,
+                    low: q.low * rate,
+                    close: q.close * rate,
+                    adjclose: q.adjclose * rate,
+                    ..q.clone()
+                })
+            })
+            .collect()
+    }
+
+    // unique uppercase currencies other than the pivot
+    fn foreign(&self, currencies: &[&str]) -> Vec<String> {
+        let mut foreign: Vec<String> = currencies
+            .iter()
+            .map(|c| c.to_uppercase())
+            .filter(|c| *c != self.pivot)
+            .collect();
+        foreign.sort();
+        foreign.dedup();
+        foreign
+    }
+
+    fn lookup(&self, from: &str, to: &str, date: Option<Date>) -> Result<f64, FxError> {
+        let (from, to) = (from.to_uppercase(), to.to_uppercase());
+        if from == to {
+            return Ok(1.0);
+        }
+        let Some(date) = date else {
+            return self
+                .find(&from, &to, None)
+                .ok_or(FxError::MissingRate(from, to));
+        };
+
+        // `max_lookback` is public, a negative value must not invert the lookup range
+        let oldest = date
+            .checked_sub(self.max_lookback.max(Duration::ZERO))
+            .unwrap_or(Date::MIN);
+        if let Some(rate) = self.find(&from, &to, Some((oldest, date))) {
+            return Ok(rate);
+        }
+        // rates older than the lookback are reported as stale rather than missing
+        match self.find(&from, &to, Some((Date::MIN, date))) {
+            Some(_) => Err(FxError::StaleRate(from, to, date)),
+            None => Err(FxError::MissingRate(from, to)),
+        }
+    }
+
+    // direct, inverse or triangulated rate, the last one within `window` (inclusive) if given
+    fn find(&self, from: &str, to: &str, window: Option<(Date, Date)>) -> Option<f64> {
+        if let Some(rate) = self.leg(from, to, window) {
+            return Some(rate);
+        }
+
+        // triangulate through any currency quoted against both sides, pivot first
+        let mut via: Vec<&String> = self
+            .rates
+            .keys()
+            .flat_map(|(a, b)| [a, b])
+            .filter(|c| *c != from && *c != to)
+            .collect();
+        via.sort_by_key(|c| (**c != self.pivot, *c));
+        via.dedup();
+        via.into_iter()
+            .find_map(|c| Some(self.leg(from, c, window)? * self.leg(c, to, window)?))
+    }
+
+    // direct or inverse rate of a currency pair
+    fn leg(&self, from: &str, to: &str, window: Option<(Date, Date)>) -> Option<f64> {
+        let at = |series: &BTreeMap<Date, f64>| match window {
+            Some((oldest, date)) => series.range(oldest..=date).next_back().map(|(_, r)| *r),
+            None => series.values().next_back().copied(),
+        };
+        self.rates
+            .get(&(from.to_string(), to.to_string()))
+            .and_then(at)
+            .or_else(|| {
+                self.rates
+                    .get(&(to.to_string(), from.to_string()))
+                    .and_then(at)
+                    .filter(|r| *r != 0.0)
+                    .map(|r| 1.0 / r)
+            })
+    }
+}
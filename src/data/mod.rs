@@ -1,2 +1,22 @@
+pub mod align;
+pub mod alphavantage;
+#[cfg(feature = "arrow")]
+pub mod arrow;
+pub mod batch;
+pub mod cache;
+pub mod csv;
 pub mod error;
+pub mod events;
+pub mod fundamentals;
+pub mod fx;
+pub mod options;
+pub mod panel;
+#[cfg(feature = "parquet")]
+pub mod parquet;
+#[cfg(feature = "polars")]
+pub mod polars;
+pub mod provider;
+pub mod request;
+pub mod resample;
+pub mod stream;
 pub mod yahoo;
@@ -0,0 +1,103 @@
+//! option chain across all expirations
+use super::yahoo::{OptionContract, OptionType};
+use ndarray::Array1;
+use time::Date;
+
+// struct to model contracts expiring on the same date
+#[derive(Debug, Clone)]
+pub struct OptionExpiry {
+    pub expiration: Date,
+    pub calls: Array1<OptionContract>,
+    pub puts: Array1<OptionContract>,
+}
+
+// struct to model the full option chain of an underlying, expiries are sorted by expiration date
+#[derive(Debug, Clone)]
+pub struct OptionChain {
+    pub underlying_symbol: String,
+    pub underlying_price: Option<f64>,
+    pub currency: Option<String>,
+    pub expiries: Vec<OptionExpiry>,
+}
+
+// struct to model option chain filters, `None` fields are not applied
+// moneyness is computed as strike / underlying price
+#[derive(Debug, Clone, Default)]
+pub struct OptionFilter {
+    pub min_expiration: Option<Date>,
+    pub max_expiration: Option<Date>,
+    pub min_strike: Option<f64>,
+    pub max_strike: Option<f64>,
+    pub min_moneyness: Option<f64>,
+    pub max_moneyness: Option<f64>,
+    pub in_the_money: Option<bool>,
+}
+
+impl OptionChain {
+    // list of available expiration dates
+    pub fn expirations(&self) -> Vec<Date> {
+        self.expiries.iter().map(|e| e.expiration).collect()
+    }
+
+    // all contracts of a given type across every expiration
+    pub fn contracts(&self, option_type: OptionType) -> Array1<OptionContract> {
+        self.expiries
+            .iter()
+            .flat_map(|e| match option_type {
+                OptionType::Call => e.calls.iter(),
+                OptionType::Put => e.puts.iter(),
+            })
+            .cloned()
+            .collect()
+    }
+
+    // build a new chain keeping only expiries and contracts matching the filter
+    // moneyness filters drop every contract when the underlying price is unknown
+    pub fn filter(&self, filter: &OptionFilter) -> OptionChain {
+        let keep = |c: &&OptionContract| {
+            let strike = match c.strike {
+                Some(s) => s,
+                None => return false,
+            };
+            if filter.min_strike.is_some_and(|m| strike < m)
+                || filter.max_strike.is_some_and(|m| strike > m)
+            {
+                return false;
+            }
+            if filter.in_the_money.is_some() && c.in_the_money != filter.in_the_money {
+                return false;
+            }
+            if filter.min_moneyness.is_some() || filter.max_moneyness.is_some() {
+                let moneyness = match self.underlying_price {
+                    Some(p) if p != 0.0 => strike / p,
+                    _ => return false,
+                };
+                if filter.min_moneyness.is_some_and(|m| moneyness < m)
+                    || filter.max_moneyness.is_some_and(|m| moneyness > m)
+                {
+                    return false;
+                }
+            }
+            true
+        };
+
+        OptionChain {
+            underlying_symbol: self.underlying_symbol.clone(),
+            underlying_price: self.underlying_price,
+            currency: self.currency.clone(),
+            expiries: self
+                .expiries
+                .iter()
+                .filter(|e| {
+                    filter.min_expiration.is_none_or(|m| e.expiration >= m)
+                        && filter.max_expiration.is_none_or(|m| e.expiration <= m)
+                })
+                .map(|e| OptionExpiry {
+                    expiration: e.expiration,
+                    calls: e.calls.iter().filter(keep).cloned().collect(),
+                    puts: e.puts.iter().filter(keep).cloned().collect(),
+                })
+                .collect(),
+        }
+    }
+}
@@ -0,0 +1,183 @@
+//! dense multi-asset price panel
+use super::yahoo::MultiQuoteItem;
+use crate::quantitative::matrix::{Matrix, MatrixError};
+use crate::quantitative::returns::{ReturnType, ReturnsError};
+use ndarray::{s, Array1, Array2, ArrayView1, Axis};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use thiserror::Error;
+use time::Date;
+
+#[derive(Debug, Error)]
+pub enum PanelError {
+    #[error("Panel values shape {0:?} does not match {1} dates and {2} tickers")]
+    ShapeMismatch(Vec<usize>, usize, usize),
+    #[error("Unknown ticker '{0}'")]
+    UnknownTicker(String),
+    #[error("Panel dates must be strictly increasing, {1} follows {0}")]
+    UnsortedDates(Date, Date),
+}
+
+// struct to model prices of multiple assets over a shared date index
+// `values` has one row per date and one column per ticker, missing prices are stored as NaN
+#[derive(Debug, Clone, PartialEq)]
+pub struct PricePanel {
+    pub dates: Vec<Date>,
+    pub tickers: Vec<String>,
+    pub values: Array2<f64>,
+}
+
+impl PricePanel {
+    // create new panel checking values shape against date index and ticker list
+    // dates must be strictly increasing, date lookups (`row`, `between`) rely on it
+    pub fn new(
+        dates: Vec<Date>,
+        tickers: Vec<String>,
+        values: Array2<f64>,
+    ) -> Result<Self, PanelError> {
+        if values.dim() != (dates.len(), tickers.len()) {
+            return Err(PanelError::ShapeMismatch(
+                values.shape().to_vec(),
+                dates.len(),
+                tickers.len(),
+            ));
+        }
+        if let Some(w) = dates.windows(2).find(|w| w[0] >= w[1]) {
+            return Err(PanelError::UnsortedDates(w[0], w[1]));
+        }
+        Ok(Self {
+            dates,
+            tickers,
+            values,
+        })
+    }
+
+    // build panel from multiquotes, tickers are sorted alphabetically and dates ascending
+    // rows sharing a date are merged, a later row overrides the prices it holds
+    pub fn from_multi_quotes(quotes: &Array1<MultiQuoteItem>) -> Self {
+        let tickers: Vec<String> = quotes
+            .iter()
+            .flat_map(|q| q.prices.keys())
+            .collect::<BTreeSet<&String>>()
+            .into_iter()
+            .cloned()
+            .collect();
+        let mut rows: BTreeMap<Date, HashMap<&String, f64>> = BTreeMap::new();
+        for q in quotes {
+            rows.entry(q.date)
+                .or_default()
+                .extend(q.prices.iter().map(|(t, p)| (t, *p)));
+        }
+        let rows: Vec<(Date, HashMap<&String, f64>)> = rows.into_iter().collect();
+
+        let values = Array2::from_shape_fn((rows.len(), tickers.len()), |(i, j)| {
+            rows[i].1.get(&tickers[j]).copied().unwrap_or(f64::NAN)
+        });
+        Self {
+            dates: rows.iter().map(|(date, _)| *date).collect(),
+            tickers,
+            values,
+        }
+    }
+
+    // convert panel back into multiquotes, NaN values are left out of each row
+    pub fn to_multi_quotes(&self) -> Array1<MultiQuoteItem> {
+        self.dates
+            .iter()
+            .zip(self.values.rows())
+            .map(|(date, row)| MultiQuoteItem {
+                date: *date,
+                prices: self
+                    .tickers
+                    .iter()
+                    .zip(row)
+                    .filter(|(_, p)| !p.is_nan())
+                    .map(|(t, p)| (t.clone(), *p))
+                    .collect::<HashMap<String, f64>>(),
+            })
+            .collect()
+    }
+
+    // number of dates and tickers
+    pub fn dim(&self) -> (usize, usize) {
+        self.values.dim()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    // prices of a single ticker over the whole date index
+    pub fn column(&self, ticker: &str) -> Result<ArrayView1<'_, f64>, PanelError> {
+        Ok(self.values.column(self.ticker_index(ticker)?))
+    }
+
+    // prices of every ticker on a given date
+    pub fn row(&self, date: Date) -> Option<ArrayView1<'_, f64>> {
+        self.dates
+            .binary_search(&date)
+            .ok()
+            .map(|i| self.values.row(i))
+    }
+
+    // new panel holding only the given tickers, in the given order
+    pub fn select(&self, tickers: &[&str]) -> Result<PricePanel, PanelError> {
+        let idx = tickers
+            .iter()
+            .map(|t| self.ticker_index(t))
+            .collect::<Result<Vec<usize>, PanelError>>()?;
+        Ok(Self {
+            dates: self.dates.clone(),
+            tickers: tickers.iter().map(|t| t.to_string()).collect(),
+            values: self.values.select(Axis(1), &idx),
+        })
+    }
+
+    // new panel holding only dates in [start, end]
+    pub fn between(&self, start: Date, end: Date) -> PricePanel {
+        let from = self.dates.partition_point(|d| *d < start);
+        let to = self.dates.partition_point(|d| *d <= end).max(from);
+        Self {
+            dates: self.dates[from..to].to_vec(),
+            tickers: self.tickers.clone(),
+            values: self.values.slice(s![from..to, ..]).to_owned(),
+        }
+    }
+
+    // compute returns of every ticker, the resulting panel starts from the second date
+    // a NaN price makes NaN the returns it takes part in
+    pub fn returns(&self, r_type: ReturnType) -> Result<PricePanel, ReturnsError> {
+        let rows = self.dates.len();
+        if rows < 2 {
+            return Err(ReturnsError::LengthError(rows));
+        }
+
+        let ratio = &self.values.slice(s![1.., ..]) / &self.values.slice(s![..rows - 1, ..]);
+        Ok(Self {
+            dates: self.dates[1..].to_vec(),
+            tickers: self.tickers.clone(),
+            values: match r_type {
+                ReturnType::Arithmetic => ratio.mapv(|r| r - 1.0),
+                ReturnType::Logarithmic => ratio.mapv(f64::ln),
+                ReturnType::Absolute => ratio,
+            },
+        })
+    }
+
+    // covariance matrix between tickers (dates are observations), ordered as `tickers`
+    pub fn covariance_matrix(&self) -> Result<Array2<f64>, MatrixError> {
+        self.values.t().to_owned().covariance_matrix()
+    }
+
+    fn ticker_index(&self, ticker: &str) -> Result<usize, PanelError> {
+        self.tickers
+            .iter()
+            .position(|t| t == ticker)
+            .ok_or_else(|| PanelError::UnknownTicker(ticker.to_string()))
+    }
+}
+
+impl From<&Array1<MultiQuoteItem>> for PricePanel {
+    fn from(quotes: &Array1<MultiQuoteItem>) -> Self {
+        Self::from_multi_quotes(quotes)
+    }
+}
@@ -0,0 +1,31 @@
+//! parquet files for quotes, panels and returns
+use super::arrow::{ArrowError, FromRecordBatch, ToRecordBatch};
+use ::arrow::array::RecordBatch;
+use ::arrow::compute::concat_batches;
+use ::parquet::arrow::{arrow_reader::ParquetRecordBatchReaderBuilder, ArrowWriter};
+use ::parquet::basic::Compression;
+use ::parquet::file::properties::WriterProperties;
+use std::{fs::File, path::Path};
+
+// write a dataset into a snappy compressed parquet file, arrow schema (timezones included) is embedded
+pub fn write_parquet<P: AsRef<Path>, T: ToRecordBatch>(
+    path: P,
+    data: &T,
+) -> Result<(), ArrowError> {
+    let batch = data.to_record_batch()?;
+    let props = WriterProperties::builder()
+        .set_compression(Compression::SNAPPY)
+        .build();
+    let mut writer = ArrowWriter::try_new(File::create(path)?, batch.schema(), Some(props))?;
+    writer.write(&batch)?;
+    writer.close()?;
+    Ok(())
+}
+
+// read a dataset from a parquet file, every row group is concatenated
+pub fn read_parquet<P: AsRef<Path>, T: FromRecordBatch>(path: P) -> Result<T, ArrowError> {
+    let builder = ParquetRecordBatchReaderBuilder::try_new(File::open(path)?)?;
+    let schema = builder.schema().clone();
+    let batches = builder.build()?.collect::<Result<Vec<RecordBatch>, _>>()?;
+    T::from_record_batch(&concat_batches(&schema, &batches)?)
+}
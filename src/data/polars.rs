@@ -0,0 +1,248 @@
+//! polars dataframe interop for quotes and returns
+use super::yahoo::{MultiQuoteItem, QuoteItem};
+use crate::commons::date::{
+    date_to_epoch_days, datetime_to_micros, epoch_days_to_date, nanos_to_exchange_dt,
+    single_timezone, DateTimeError,
+};
+use ::polars::prelude::{
+    Column, DataFrame, Int32Chunked, Int64Chunked, IntoColumn, PolarsError, TimeUnit, TimeZone,
+};
+use ndarray::Array1;
+use std::collections::{BTreeSet, HashMap};
+use thiserror::Error;
+use time::{Date, OffsetDateTime};
+
+#[derive(Debug, Error)]
+pub enum DataFrameError {
+    #[error("Polars operation failed: {0}")]
+    Polars(#[from] PolarsError),
+    #[error("Column '{0}' contains null values.")]
+    NullValue(String),
+    #[error("Invalid timestamp or date in column '{0}'")]
+    InvalidTimestamp(String),
+    #[error("Unknown timezone '{0}'")]
+    UnknownTimezone(String),
+    #[error("Failed to convert quote datetimes: {0}")]
+    DateTime(#[from] DateTimeError),
+}
+
+// date with prices (or returns) keyed by ticker
+type DatedValues = (Date, HashMap<String, f64>);
+
+// trait to model conversion of a dataset into a polars dataframe
+pub trait ToDataFrame {
+    fn to_dataframe(&self) -> Result<DataFrame, DataFrameError>;
+}
+
+// trait to model conversion of a polars dataframe back into a dataset
+pub trait FromDataFrame: Sized {
+    fn from_dataframe(df: &DataFrame) -> Result<Self, DataFrameError>;
+}
+
+// single asset quotes: `datetime` (microseconds, exchange timezone) followed by
+// open, high, low, close, adjclose (f64) and volume (u64)
+// the quotes timezone is used for the whole column, quotes in different timezones are rejected
+impl ToDataFrame for Array1<QuoteItem> {
+    fn to_dataframe(&self) -> Result<DataFrame, DataFrameError> {
+        let timezone = single_timezone(self)?;
+        let price = |name: &str, f: fn(&QuoteItem) -> f64| {
+            Column::new(name.into(), self.iter().map(f).collect::<Vec<f64>>())
+        };
+        Ok(DataFrame::new(vec![
+            datetime_column(self.iter().map(|q| q.datetime), timezone)?,
+            price("open", |q| q.open),
+            price("high", |q| q.high),
+            price("low", |q| q.low),
+            price("close", |q| q.close),
+            price("adjclose", |q| q.adjclose),
+            Column::new(
+                "volume".into(),
+                self.iter().map(|q| q.volume).collect::<Vec<u64>>(),
+            ),
+        ])?)
+    }
+}
+
+impl FromDataFrame for Array1<QuoteItem> {
+    fn from_dataframe(df: &DataFrame) -> Result<Self, DataFrameError> {
+        let (datetime, timezone) = datetime_values(df, "datetime")?;
+        let open = f64_values(df, "open")?;
+        let high = f64_values(df, "high")?;
+        let low = f64_values(df, "low")?;
+        let close = f64_values(df, "close")?;
+        let adjclose = f64_values(df, "adjclose")?;
+        let volume = df
+            .column("volume")?
+            .u64()?
+            .into_iter()
+            .map(|v| v.ok_or_else(|| DataFrameError::NullValue("volume".to_string())))
+            .collect::<Result<Vec<u64>, DataFrameError>>()?;
+
+        Ok((0..df.height())
+            .map(|i| QuoteItem {
+                datetime: datetime[i],
+                timezone: timezone.clone(),
+                open: open[i],
+                high: high[i],
+                low: low[i],
+                close: close[i],
+                adjclose: adjclose[i],
+                volume: volume[i],
+            })
+            .collect())
+    }
+}
+
+// multiquotes: `date` column followed by one f64 column per ticker (alphabetical order)
+// missing prices are nulls and are left out of the date prices when read back
+impl ToDataFrame for Array1<MultiQuoteItem> {
+    fn to_dataframe(&self) -> Result<DataFrame, DataFrameError> {
+        wide_dataframe(self.iter().map(|q| (q.date, &q.prices)))
+    }
+}
+
+impl FromDataFrame for Array1<MultiQuoteItem> {
+    fn from_dataframe(df: &DataFrame) -> Result<Self, DataFrameError> {
+        Ok(read_wide(df)?
+            .into_iter()
+            .map(|(date, prices)| MultiQuoteItem { date, prices })
+            .collect())
+    }
+}
+
+// returns series: `datetime` (microseconds, utc) and `return` (f64)
+impl ToDataFrame for Array1<(OffsetDateTime, f64)> {
+    fn to_dataframe(&self) -> Result<DataFrame, DataFrameError> {
+        Ok(DataFrame::new(vec![
+            datetime_column(self.iter().map(|(d, _)| *d), "UTC")?,
+            Column::new(
+                "return".into(),
+                self.iter().map(|(_, r)| *r).collect::<Vec<f64>>(),
+            ),
+        ])?)
+    }
+}
+
+impl FromDataFrame for Array1<(OffsetDateTime, f64)> {
+    fn from_dataframe(df: &DataFrame) -> Result<Self, DataFrameError> {
+        let (datetime, _) = datetime_values(df, "datetime")?;
+        let returns = f64_values(df, "return")?;
+        Ok(datetime.into_iter().zip(returns).collect())
+    }
+}
+
+// multi-asset returns: same wide layout as multiquotes
+impl ToDataFrame for Array1<DatedValues> {
+    fn to_dataframe(&self) -> Result<DataFrame, DataFrameError> {
+        wide_dataframe(self.iter().map(|(d, r)| (*d, r)))
+    }
+}
+
+impl FromDataFrame for Array1<DatedValues> {
+    fn from_dataframe(df: &DataFrame) -> Result<Self, DataFrameError> {
+        Ok(Array1::from_vec(read_wide(df)?))
+    }
+}
+
+// get a dataframe f64 column as array (e.g. to compute `Statistic` metrics), nulls are not allowed
+pub fn column_array(df: &DataFrame, name: &str) -> Result<Array1<f64>, DataFrameError> {
+    Ok(Array1::from_vec(f64_values(df, name)?))
+}
+
+// helper function to build a microsecond datetime column in the given timezone
+fn datetime_column(
+    datetimes: impl Iterator<Item = OffsetDateTime>,
+    timezone: &str,
+) -> Result<Column, DataFrameError> {
+    let micros: Vec<i64> = datetimes.map(datetime_to_micros).collect();
+    Ok(Int64Chunked::from_vec("datetime".into(), micros)
+        .into_datetime(
+            TimeUnit::Microseconds,
+            TimeZone::opt_try_new(Some(timezone))?,
+        )
+        .into_column())
+}
+
+// helper function to build a wide dataframe from dated ticker maps
+fn wide_dataframe<'a>(
+    rows: impl Iterator<Item = (Date, &'a HashMap<String, f64>)> + Clone,
+) -> Result<DataFrame, DataFrameError> {
+    let tickers: BTreeSet<&String> = rows.clone().flat_map(|(_, p)| p.keys()).collect();
+    let days: Vec<i32> = rows.clone().map(|(d, _)| date_to_epoch_days(d)).collect();
+
+    let mut columns = vec![Int32Chunked::from_vec("date".into(), days)
+        .into_date()
+        .into_column()];
+    columns.extend(tickers.into_iter().map(|t| {
+        Column::new(
+            t.into(),
+            rows.clone()
+                .map(|(_, p)| p.get(t).copied())
+                .collect::<Vec<Option<f64>>>(),
+        )
+    }));
+    Ok(DataFrame::new(columns)?)
+}
+
+// helper function to read a wide dataframe into dated ticker maps, nulls are skipped
+fn read_wide(df: &DataFrame) -> Result<Vec<DatedValues>, DataFrameError> {
+    let dates = df
+        .column("date")?
+        .date()?
+        .physical()
+        .into_iter()
+        .map(|d| {
+            d.and_then(|d| epoch_days_to_date(d).ok())
+                .ok_or_else(|| DataFrameError::InvalidTimestamp("date".to_string()))
+        })
+        .collect::<Result<Vec<Date>, DataFrameError>>()?;
+
+    let mut rows: Vec<DatedValues> = dates.into_iter().map(|d| (d, HashMap::new())).collect();
+    for col in df.get_columns().iter().filter(|c| c.name() != "date") {
+        for (i, v) in col.f64()?.into_iter().enumerate() {
+            if let Some(v) = v {
+                rows[i].1.insert(col.name().to_string(), v);
+            }
+        }
+    }
+    Ok(rows)
+}
+
+fn f64_values(df: &DataFrame, name: &str) -> Result<Vec<f64>, DataFrameError> {
+    df.column(name)?
+        .f64()?
+        .into_iter()
+        .map(|v| v.ok_or_else(|| DataFrameError::NullValue(name.to_string())))
+        .collect()
+}
+
+// helper function to read a datetime column of any time unit, returns datetimes in the column
+// timezone (utc when not set) and the timezone name
+fn datetime_values(
+    df: &DataFrame,
+    name: &str,
+) -> Result<(Vec<OffsetDateTime>, String), DataFrameError> {
+    let col = df.column(name)?.datetime()?;
+    let timezone = col
+        .time_zone()
+        .as_ref()
+        .map_or("UTC".to_string(), |tz| tz.to_string());
+    let nanos_per_unit: i128 = match col.time_unit() {
+        TimeUnit::Nanoseconds => 1,
+        TimeUnit::Microseconds => 1_000,
+        TimeUnit::Milliseconds => 1_000_000,
+    };
+
+    let datetimes = col
+        .physical()
+        .into_iter()
+        .map(|v| {
+            let v = v.ok_or_else(|| DataFrameError::NullValue(name.to_string()))?;
+            nanos_to_exchange_dt(v as i128 * nanos_per_unit, &timezone).map_err(|e| match e {
+                DateTimeError::UnknownTimezone(tz) => DataFrameError::UnknownTimezone(tz),
+                _ => DataFrameError::InvalidTimestamp(name.to_string()),
+            })
+        })
+        .collect::<Result<Vec<OffsetDateTime>, DataFrameError>>()?;
+    Ok((datetimes, timezone))
+}
@@ -0,0 +1,47 @@
+//! market data provider abstraction
+use super::yahoo::{MultiQuoteItem, OptionContract, OptionType, QuoteItem};
+use ndarray::Array1;
+use std::{error::Error, future::Future};
+
+// trait to model a generic source of market data (e.g. yahoo! finance, in-memory fixtures)
+pub trait MarketDataProvider: Send + Sync {
+    type Error: Error + Send + Sync + 'static;
+
+    // get quotation data for single asset query
+    fn get_quotes(
+        &self,
+        symbol: &str,
+        start_date: Option<&str>,
+        end_date: Option<&str>,
+        period: Option<&str>,
+        interval: Option<&str>,
+    ) -> impl Future<Output = Result<Array1<QuoteItem>, Self::Error>> + Send;
+
+    // get quotation data for multiple assets query
+    fn get_multiple_quotes(
+        &self,
+        tickers: Vec<&str>,
+        start_date: Option<&str>,
+        end_date: Option<&str>,
+        period: Option<&str>,
+        interval: Option<&str>,
+    ) -> impl Future<Output = Result<Array1<MultiQuoteItem>, Self::Error>> + Send;
+
+    // get latest quotation for an asset
+    fn get_latest_quote(
+        &self,
+        ticker: &str,
+    ) -> impl Future<Output = Result<f64, Self::Error>> + Send;
+
+    // get asset options data
+    fn get_options(
+        &self,
+        ticker: &str,
+        option_type: OptionType,
+    ) -> impl Future<Output = Result<Array1<OptionContract>, Self::Error>> + Send;
+
+    // whether a failed request is worth retrying (e.g. network hiccups, rate limiting)
+    fn is_transient(&self, _error: &Self::Error) -> bool {
+        false
+    }
+}
@@ -0,0 +1,284 @@
+//! typed yahoo! finance quote requests
+use super::yahoo::YahooErr;
+use crate::commons::date::{parse_date, DateType};
+use std::fmt;
+use std::str::FromStr;
+use time::OffsetDateTime;
+
+// bar interval supported by yahoo! finance chart api
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Interval {
+    OneMinute,
+    TwoMinutes,
+    FiveMinutes,
+    FifteenMinutes,
+    ThirtyMinutes,
+    SixtyMinutes,
+    NinetyMinutes,
+    OneHour,
+    OneDay,
+    FiveDays,
+    OneWeek,
+    OneMonth,
+    ThreeMonths,
+}
+
+impl Interval {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Interval::OneMinute => "1m",
+            Interval::TwoMinutes => "2m",
+            Interval::FiveMinutes => "5m",
+            Interval::FifteenMinutes => "15m",
+            Interval::ThirtyMinutes => "30m",
+            Interval::SixtyMinutes => "60m",
+            Interval::NinetyMinutes => "90m",
+            Interval::OneHour => "1h",
+            Interval::OneDay => "1d",
+            Interval::FiveDays => "5d",
+            Interval::OneWeek => "1wk",
+            Interval::OneMonth => "1mo",
+            Interval::ThreeMonths => "3mo",
+        }
+    }
+
+    // how far back (in days) yahoo! finance keeps bars of this interval, `None` if there is no limit
+    pub fn max_lookback_days(&self) -> Option<i64> {
+        match self {
+            Interval::OneMinute => Some(30),
+            Interval::TwoMinutes
+            | Interval::FiveMinutes
+            | Interval::FifteenMinutes
+            | Interval::ThirtyMinutes
+            | Interval::NinetyMinutes => Some(60),
+            Interval::SixtyMinutes | Interval::OneHour => Some(730),
+            _ => None,
+        }
+    }
+
+    // longest date range (in days) a single request of this interval can span, `None` if there is no limit
+    pub fn max_span_days(&self) -> Option<i64> {
+        match self {
+            Interval::OneMinute => Some(8),
+            _ => self.max_lookback_days(),
+        }
+    }
+}
+
+impl FromStr for Interval {
+    type Err = YahooErr;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "1m" => Ok(Interval::OneMinute),
+            "2m" => Ok(Interval::TwoMinutes),
+            "5m" => Ok(Interval::FiveMinutes),
+            "15m" => Ok(Interval::FifteenMinutes),
+            "30m" => Ok(Interval::ThirtyMinutes),
+            "60m" => Ok(Interval::SixtyMinutes),
+            "90m" => Ok(Interval::NinetyMinutes),
+            "1h" => Ok(Interval::OneHour),
+            "1d" => Ok(Interval::OneDay),
+            "5d" => Ok(Interval::FiveDays),
+            "1wk" => Ok(Interval::OneWeek),
+            "1mo" => Ok(Interval::OneMonth),
+            "3mo" => Ok(Interval::ThreeMonths),
+            _ => Err(YahooErr::InvalidInterval(s.to_string())),
+        }
+    }
+}
+
+impl fmt::Display for Interval {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+// period ending today supported by yahoo! finance chart api
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Range {
+    OneDay,
+    FiveDays,
+    OneMonth,
+    ThreeMonths,
+    SixMonths,
+    OneYear,
+    TwoYears,
+    FiveYears,
+    TenYears,
+    YearToDate,
+    Max,
+}
+
+impl Range {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Range::OneDay => "1d",
+            Range::FiveDays => "5d",
+            Range::OneMonth => "1mo",
+            Range::ThreeMonths => "3mo",
+            Range::SixMonths => "6mo",
+            Range::OneYear => "1y",
+            Range::TwoYears => "2y",
+            Range::FiveYears => "5y",
+            Range::TenYears => "10y",
+            Range::YearToDate => "ytd",
+            Range::Max => "max",
+        }
+    }
+
+    // approximate number of calendar days covered by the period as of `today`, `None` for `Max`
+    pub fn days(&self, today: OffsetDateTime) -> Option<i64> {
+        match self {
+            Range::OneDay => Some(1),
+            Range::FiveDays => Some(5),
+            Range::OneMonth => Some(31),
+            Range::ThreeMonths => Some(92),
+            Range::SixMonths => Some(183),
+            Range::OneYear => Some(365),
+            Range::TwoYears => Some(730),
+            Range::FiveYears => Some(1827),
+            Range::TenYears => Some(3653),
+            Range::YearToDate => Some(today.ordinal() as i64),
+            Range::Max => None,
+        }
+    }
+}
+
+impl FromStr for Range {
+    type Err = YahooErr;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "1d" => Ok(Range::OneDay),
+            "5d" => Ok(Range::FiveDays),
+            "1mo" => Ok(Range::OneMonth),
+            "3mo" => Ok(Range::ThreeMonths),
+            "6mo" => Ok(Range::SixMonths),
+            "1y" => Ok(Range::OneYear),
+            "2y" => Ok(Range::TwoYears),
+            "5y" => Ok(Range::FiveYears),
+            "10y" => Ok(Range::TenYears),
+            "ytd" => Ok(Range::YearToDate),
+            "max" => Ok(Range::Max),
+            _ => Err(YahooErr::InvalidRange(s.to_string())),
+        }
+    }
+}
+
+impl fmt::Display for Range {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+// time span covered by a quote request
+#[derive(Debug, Clone, PartialEq)]
+pub enum Span {
+    Range(Range),
+    Dates(OffsetDateTime, OffsetDateTime),
+}
+
+// struct to model a validated quote request, build it through `QuoteRequest::builder`
+#[derive(Debug, Clone, PartialEq)]
+pub struct QuoteRequest {
+    pub symbol: String,
+    pub interval: Interval,
+    pub span: Span,
+}
+
+impl QuoteRequest {
+    pub fn builder(symbol: &str) -> QuoteRequestBuilder {
+        QuoteRequestBuilder {
+            symbol: symbol.to_string(),
+            interval: Interval::OneMinute,
+            range: None,
+            dates: None,
+        }
+    }
+}
+
+// quote request builder, defaults to 1m bars of the current trading day
+// a date range takes precedence over a period when both are set
+#[derive(Debug, Clone)]
+pub struct QuoteRequestBuilder {
+    symbol: String,
+    interval: Interval,
+    range: Option<Range>,
+    dates: Option<(String, String)>,
+}
+
+impl QuoteRequestBuilder {
+    pub fn interval(mut self, interval: Interval) -> Self {
+        self.interval = interval;
+        self
+    }
+
+    pub fn range(mut self, range: Range) -> Self {
+        self.range = Some(range);
+        self
+    }
+
+    // set date range, dates are validated on `build`
+    pub fn between(mut self, start_date: &str, end_date: &str) -> Self {
+        self.dates = Some((start_date.to_string(), end_date.to_string()));
+        self
+    }
+
+    // validate interval and time span combination against yahoo! finance limits
+    pub fn build(self) -> Result<QuoteRequest, YahooErr> {
+        self.build_at(OffsetDateTime::now_utc())
+    }
+
+    // validate request as of a given instant
+    pub fn build_at(self, now: OffsetDateTime) -> Result<QuoteRequest, YahooErr> {
+        let interval = self.interval;
+        let span = match (self.dates, self.range) {
+            (Some((start_date, end_date)), _) => {
+                let start = parse_date(&start_date, DateType::Start)?;
+                let end = parse_date(&end_date, DateType::End)?;
+                if start > end {
+                    return Err(YahooErr::InvalidRequest(format!(
+                        "start date {} is after end date {}",
+                        start_date, end_date
+                    )));
+                }
+                if let Some(max) = interval.max_lookback_days() {
+                    if (now - start).whole_days() > max {
+                        return Err(YahooErr::InvalidRequest(format!(
+                            "{} bars are only available for the last {} days, requested from {}",
+                            interval, max, start_date
+                        )));
+                    }
+                }
+                if let Some(max) = interval.max_span_days() {
+                    if (end - start).whole_days() > max {
+                        return Err(YahooErr::InvalidRequest(format!(
+                            "{} requests cannot span more than {} days, requested {} to {}",
+                            interval, max, start_date, end_date
+                        )));
+                    }
+                }
+                Span::Dates(start, end)
+            }
+            (None, range) => {
+                let range = range.unwrap_or(Range::OneDay);
+                if let Some(max) = interval.max_span_days() {
+                    if range.days(now).is_none_or(|days| days > max) {
+                        return Err(YahooErr::InvalidRequest(format!(
+                            "{} requests cannot span more than {} days, requested period {}",
+                            interval, max, range
+                        )));
+                    }
+                }
+                Span::Range(range)
+            }
+        };
+
+        Ok(QuoteRequest {
+            symbol: self.symbol,
+            interval,
+            span,
+        })
+    }
+}
@@ -0,0 +1,149 @@
+//! resampling of quotation bars to coarser frequencies
+use super::yahoo::QuoteItem;
+use crate::commons::date::{local_to_exchange_dt, DateTimeError};
+use ndarray::Array1;
+use std::str::FromStr;
+use thiserror::Error;
+use time::{Date, Duration, Month, PrimitiveDateTime, Time};
+
+#[derive(Debug, Error)]
+pub enum ResampleError {
+    #[error("Invalid resampling frequency '{0}'")]
+    InvalidFrequency(String),
+    #[error("Failed to label resampled bar: {0}")]
+    DateTime(#[from] DateTimeError),
+}
+
+// target frequency of resampled bars
+// intraday buckets follow the exchange local clock from an origin time (midnight by default, e.g. 09:30 gives
+// 09:30, 10:30, ... for 1h bars), calendar buckets follow exchange local dates
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Frequency {
+    Minutes(u32),
+    Hours(u32),
+    Daily,
+    Weekly,
+    MonthEnd,
+    QuarterEnd,
+}
+
+// parse yahoo! finance style interval strings (e.g. "5m", "1h", "1d", "1wk", "1mo", "3mo")
+impl FromStr for Frequency {
+    type Err = ResampleError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || ResampleError::InvalidFrequency(s.to_string());
+        let split = s.find(|c: char| !c.is_ascii_digit()).ok_or_else(invalid)?;
+        let n: u32 = s[..split].parse().map_err(|_| invalid())?;
+        match (n, &s[split..]) {
+            (0, _) => Err(invalid()),
+            (n, "m") => Ok(Frequency::Minutes(n)),
+            (n, "h") => Ok(Frequency::Hours(n)),
+            (1, "d") => Ok(Frequency::Daily),
+            (1, "wk") => Ok(Frequency::Weekly),
+            (1, "mo") => Ok(Frequency::MonthEnd),
+            (3, "mo") => Ok(Frequency::QuarterEnd),
+            _ => Err(invalid()),
+        }
+    }
+}
+
+pub trait Resample {
+    // resample with intraday buckets on the local clock from midnight
+    fn resample(&self, freq: Frequency) -> Result<Array1<QuoteItem>, ResampleError> {
+        self.resample_from(freq, Time::MIDNIGHT)
+    }
+
+    // resample with intraday buckets on the local clock from `origin`, usually the session open
+    fn resample_from(
+        &self,
+        freq: Frequency,
+        origin: Time,
+    ) -> Result<Array1<QuoteItem>, ResampleError>;
+}
+
+impl Resample for Array1<QuoteItem> {
+    // aggregate bars into `freq` buckets: first open, highest high, lowest low, last close and adjusted close, summed volume
+    // intraday bars are labelled with their bucket start, calendar bars with the last trading date of the period (local midnight)
+    // a missing bar leaves its bucket out without moving the others
+    fn resample_from(
+        &self,
+        freq: Frequency,
+        origin: Time,
+    ) -> Result<Array1<QuoteItem>, ResampleError> {
+        let seconds = match freq {
+            Frequency::Minutes(0) | Frequency::Hours(0) => {
+                return Err(ResampleError::InvalidFrequency(format!("{:?}", freq)))
+            }
+            Frequency::Minutes(n) => Some(n as i64 * 60),
+            Frequency::Hours(n) => Some(n as i64 * 3600),
+            _ => None,
+        };
+        let origin = seconds_of_day(origin);
+
+        let mut quotes: Vec<&QuoteItem> = self.iter().collect();
+        quotes.sort_by_key(|q| q.datetime);
+
+        let mut bars: Vec<QuoteItem> = Vec::new();
+        let mut current: Option<(Date, i64)> = None;
+        for q in quotes {
+            let date = q.datetime.date();
+            let (key, start) = match seconds {
+                Some(n) => {
+                    let bucket = (seconds_of_day(q.datetime.time()) - origin).div_euclid(n);
+                    (
+                        (date, bucket),
+                        PrimitiveDateTime::new(date, Time::MIDNIGHT)
+                            + Duration::seconds(origin + bucket * n),
+                    )
+                }
+                None => (
+                    (period_start(date, freq), 0),
+                    PrimitiveDateTime::new(date, Time::MIDNIGHT),
+                ),
+            };
+
+            match bars.last_mut() {
+                Some(bar) if current == Some(key) => {
+                    bar.high = bar.high.max(q.high);
+                    bar.low = bar.low.min(q.low);
+                    bar.close = q.close;
+                    bar.adjclose = q.adjclose;
+                    bar.volume += q.volume;
+                    if seconds.is_none() {
+                        bar.datetime = local_to_exchange_dt(start, &q.timezone)?;
+                    }
+                }
+                _ => {
+                    current = Some(key);
+                    bars.push(QuoteItem {
+                        datetime: local_to_exchange_dt(start, &q.timezone)?,
+                        ..q.clone()
+                    });
+                }
+            }
+        }
+
+        Ok(Array1::from_vec(bars))
+    }
+}
+
+// helper function to get the seconds elapsed since midnight
+fn seconds_of_day(time: Time) -> i64 {
+    let (h, m, s) = time.as_hms();
+    h as i64 * 3600 + m as i64 * 60 + s as i64
+}
+
+// helper function to get the first date of the calendar period a date falls into
+fn period_start(date: Date, freq: Frequency) -> Date {
+    match freq {
+        Frequency::Weekly => date - Duration::days(date.weekday().number_days_from_monday() as i64),
+        Frequency::MonthEnd => date.replace_day(1).unwrap_or(date),
+        Frequency::QuarterEnd => {
+            let month =
+                Month::try_from((date.month() as u8 - 1) / 3 * 3 + 1).unwrap_or(date.month());
+            Date::from_calendar_date(date.year(), month, 1).unwrap_or(date)
+        }
+        _ => date,
+    }
+}
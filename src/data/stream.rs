@@ -0,0 +1,121 @@
+//! polling based real-time quote stream
+use super::provider::MarketDataProvider;
+use futures::{future::join_all, stream, Stream};
+use std::{
+    collections::{HashMap, VecDeque},
+    time::Duration,
+};
+use time::OffsetDateTime;
+use tokio::time::{interval, Interval, MissedTickBehavior};
+
+// struct to model a price update, `datetime` is the time the price was polled (utc)
+#[derive(Debug, Clone, PartialEq)]
+pub struct QuoteTick {
+    pub symbol: String,
+    pub price: f64,
+    pub datetime: OffsetDateTime,
+}
+
+// stream item, failed polls carry the ticker alongside the provider error
+pub type QuoteUpdate<E> = Result<QuoteTick, (String, E)>;
+
+// struct to model quote stream options
+// `interval` is the time between two polls, clamped to at least `MIN_POLL_INTERVAL` (one second),
+// `skip_unchanged` drops ticks whose price did not move
+#[derive(Debug, Clone)]
+pub struct StreamConfig {
+    pub interval: Duration,
+    pub skip_unchanged: bool,
+}
+
+impl Default for StreamConfig {
+    fn default() -> Self {
+        Self {
+            interval: Duration::from_secs(5),
+            skip_unchanged: true,
+        }
+    }
+}
+
+// state carried between polls of the quote stream
+struct StreamState {
+    tickers: Vec<String>,
+    last: HashMap<String, f64>,
+    timer: Interval,
+}
+
+// endless stream of quote updates for a set of tickers, polling the provider latest quote
+// every ticker is polled concurrently, the first poll happens immediately and a failing ticker
+// does not end the stream
+pub fn quote_stream<'a, P: MarketDataProvider>(
+    provider: &'a P,
+    tickers: &[&str],
+    config: StreamConfig,
+) -> impl Stream<Item = QuoteUpdate<P::Error>> + Send + 'a {
+    let state = StreamState {
+        tickers: tickers.iter().map(|t| t.to_string()).collect(),
+        last: HashMap::new(),
+        timer: poll_timer(config.interval),
+    };
+
+    stream::unfold(
+        (state, VecDeque::new()),
+        move |(mut state, mut pending)| async move {
+            loop {
+                if let Some(update) = pending.pop_front() {
+                    return Some((update, (state, pending)));
+                }
+
+                state.timer.tick().await;
+                for update in poll_quotes(provider, &state.tickers).await {
+                    if let Ok(tick) = &update {
+                        if config.skip_unchanged
+                            && state.last.get(&tick.symbol) == Some(&tick.price)
+                        {
+                            continue;
+                        }
+                        state.last.insert(tick.symbol.clone(), tick.price);
+                    }
+                    pending.push_back(update);
+                }
+            }
+        },
+    )
+}
+
+// poll the latest quote of every ticker once and concurrently, updates keep the tickers order
+pub async fn poll_quotes<P: MarketDataProvider>(
+    provider: &P,
+    tickers: &[String],
+) -> Vec<QuoteUpdate<P::Error>> {
+    let polls = join_all(
+        tickers
+            .iter()
+            .map(|t| async move { (t.clone(), provider.get_latest_quote(t).await) }),
+    )
+    .await;
+    let now = OffsetDateTime::now_utc();
+    polls
+        .into_iter()
+        .map(|(symbol, r)| match r {
+            Ok(price) => Ok(QuoteTick {
+                symbol,
+                price,
+                datetime: now,
+            }),
+            Err(e) => Err((symbol, e)),
+        })
+        .collect()
+}
+
+// shortest time between two polls, shorter intervals (zero included) are raised to it so a
+// stream can not hammer the provider into rate limiting
+pub const MIN_POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+// helper function to build the poll timer, the first tick completes immediately
+// and late polls push the following ones back instead of bursting
+pub(crate) fn poll_timer(period: Duration) -> Interval {
+    let mut timer = interval(period.max(MIN_POLL_INTERVAL));
+    timer.set_missed_tick_behavior(MissedTickBehavior::Delay);
+    timer
+}
@@ -1,45 +1,47 @@
-//use super::error::YahooErr;
-use crate::commons::{
-    date::{datetime_to_date, parse_date, timestamp_to_localdt, DateType},
-    parser::round_to_three,
-};
+use super::batch::{batch_requests, get_quotes_batch, BatchConfig, BatchQuotes};
+pub use super::error::YahooErr;
+use super::events::{DividendItem, SplitItem};
+use super::fundamentals::{BatchSummaries, QuoteSummary, YSummaryResponse};
+use super::options::{OptionChain, OptionExpiry};
+use super::provider::MarketDataProvider;
+use super::request::{Interval, QuoteRequest, Range, Span};
+use crate::commons::date::{parse_date, timestamp_to_datetime, timestamp_to_exchange_dt, DateType};
 use ndarray::Array1;
+use reqwest::{
+    header::{COOKIE, SET_COOKIE},
+    Client,
+};
+use serde::{Deserialize, Serialize};
 use sqlx::Result;
-use std::{collections::HashMap, error::Error};
-use thiserror::Error;
-use yahoofinance::{Quote, YOptionContract, YSearchResult, YahooConnector};
-
-#[derive(Debug, Error)]
-pub enum YahooErr {
-    #[error("Failed to fetch data from yahoo! finance: {0}")]
-    FetchFailed(String),
-    #[error("Failed to deserialize from yahoo! finance: {0}")]
-    DeserializationFailed(#[from] serde_json::Error),
-    #[error("Request to yahoo! finance servers failed: {0}")]
-    RequestFailed(#[from] reqwest::Error),
-    #[error("Yahoo! finance returned invalid JSON format.")]
-    InvalidJson,
-    #[error("Yahoo! finance returned an empty data set.")]
-    EmptyDataSet,
-    #[error("Yahoo! Finance returned inconsistent data: {0}")]
-    DataInconsistency(String),
-    #[error("Failed to build Yahoo! Finance client.")]
-    BuilderFailed,
-    #[error(
-        "Failed to parse Yahoo! Finance date format. Response returned invalid date format: {0}"
-    )]
-    InvalidDateFormat(String),
-}
+use std::collections::HashMap;
+use time::{Date, OffsetDateTime};
+use yahoofinance::{YSearchResult, YSearchResultOpt, YahooConnector};
+
+// yahoo! finance api endpoints
+const YBASE_URL: &str = "https://query2.finance.yahoo.com";
+const YCOOKIE_URL: &str = "https://fc.yahoo.com";
+const YSUMMARY_MODULES: &str = "price,summaryDetail,defaultKeyStatistics,assetProfile";
+const YUSER_AGENT: &str =
+    "Mozilla/5.0 (X11; Linux x86_64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/120.0 Safari/537.36";
 
 // struct to model connection with yahoo! finance
+// `base_url` serves every api endpoint, `cookie_url` only hands out the session cookie
 pub struct Yahoo {
+    #[deprecated(
+        note = "api requests go through `client` and `base_url`, use the `Yahoo` methods instead"
+    )]
     pub provider: YahooConnector,
+    pub client: Client,
+    pub base_url: String,
+    pub cookie_url: String,
 }
 
-// struct to model yahoo! finance quotes with datetime string instead of timestamp
-#[derive(Debug, Clone)]
+// struct to model yahoo! finance quotes with datetime in exchange local time instead of timestamp
+// `timezone` is the exchange IANA timezone name (e.g. "Europe/Rome", "America/New_York")
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct QuoteItem {
-    pub datetime: String,
+    pub datetime: OffsetDateTime,
+    pub timezone: String,
     pub open: f64,
     pub high: f64,
     pub low: f64,
@@ -48,15 +50,16 @@ pub struct QuoteItem {
     pub volume: u64,
 }
 
-// struct to model yahoo! finance quotes into mutiplte quotes
+// struct to model yahoo! finance quotes into mutiplte quotes (keyed by exchange local date)
 #[derive(Debug, Clone)]
 pub struct MultiQuoteItem {
-    pub date: String,
+    pub date: Date,
     pub prices: HashMap<String, f64>,
 }
 
 // struct to model yahoo! finance options
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
 pub struct OptionContract {
     pub contract_symbol: Option<String>,
     pub strike: Option<f64>,
@@ -75,7 +78,21 @@ pub struct OptionContract {
     pub in_the_money: Option<bool>,
 }
 
+// struct to model yahoo! finance asset search results
+#[derive(Debug, Clone, Deserialize)]
+pub struct SearchItem {
+    pub symbol: String,
+    #[serde(rename = "shortname")]
+    pub short_name: Option<String>,
+    #[serde(rename = "longname")]
+    pub long_name: Option<String>,
+    pub exchange: Option<String>,
+    #[serde(rename = "quoteType")]
+    pub quote_type: Option<String>,
+}
+
 // option type enumerator
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub enum OptionType {
     Call,
     Put,
@@ -83,9 +100,26 @@ pub enum OptionType {
 
 // implementation of Yahoo struct
 impl Yahoo {
+    #[allow(deprecated)]
     pub fn provider() -> Result<Self, YahooErr> {
         let provider = YahooConnector::new().map_err(|_| YahooErr::BuilderFailed)?;
-        Ok(Self { provider })
+        let client = Client::builder()
+            .user_agent(YUSER_AGENT)
+            .build()
+            .map_err(|_| YahooErr::BuilderFailed)?;
+        Ok(Self {
+            provider,
+            client,
+            base_url: YBASE_URL.to_string(),
+            cookie_url: YCOOKIE_URL.to_string(),
+        })
+    }
+
+    // point the provider to another server (e.g. a local mock server), session cookie included
+    pub fn with_base_url(mut self, base_url: &str) -> Self {
+        self.base_url = base_url.trim_end_matches('/').to_string();
+        self.cookie_url = self.base_url.clone();
+        self
     }
 
     // get quotation data for single asset query
@@ -98,69 +132,73 @@ impl Yahoo {
         interval: Option<&str>,
     ) -> Result<Array1<QuoteItem>, YahooErr> {
         // set default interval if not passed as argument
-        let interval = interval.unwrap_or("1m");
+        let mut request = QuoteRequest::builder(symbol).interval(interval.unwrap_or("1m").parse()?);
 
-        // case 1: get data for specific date range
+        // date range takes precedence over period, default is current trading day
         if let (Some(start_date), Some(end_date)) = (start_date, end_date) {
-            let start_dt = parse_date(start_date, DateType::Start)
-                .map_err(|_| YahooErr::InvalidDateFormat(start_date.to_string()))?;
-            let end_dt = parse_date(end_date, DateType::End)
-                .map_err(|_| YahooErr::InvalidDateFormat(end_date.to_string()))?;
-            return convert_to_quoteitem(
-                self.provider
-                    .get_quote_history_interval(symbol, start_dt, end_dt, interval)
-                    .await
-                    .map_err(|err| YahooErr::FetchFailed(err.to_string()))?
-                    .quotes()
-                    .map_err(|err| {
-                        if err.to_string().contains("EOF") || err.to_string().contains("empty") {
-                            YahooErr::EmptyDataSet
-                        } else {
-                            YahooErr::InvalidJson
-                        }
-                    })?,
-            )
-            .map_err(|e| YahooErr::DataInconsistency(e.to_string()));
+            request = request.between(start_date, end_date);
+        } else if let Some(period) = period {
+            request = request.range(period.parse()?);
         }
 
-        // case 2: get data for defined period
-        if let Some(period) = period {
-            return convert_to_quoteitem(
-                self.provider
-                    .get_quote_range(symbol, interval, period)
-                    .await
-                    .map_err(|err| YahooErr::FetchFailed(err.to_string()))?
-                    .quotes()
-                    .map_err(|err| {
-                        if err.to_string().contains("EOF") || err.to_string().contains("empty") {
-                            YahooErr::EmptyDataSet
-                        } else {
-                            YahooErr::InvalidJson
-                        }
-                    })?,
-            )
-            .map_err(|e| YahooErr::DataInconsistency(e.to_string()));
+        self.fetch_quotes(&request.build()?).await
+    }
+
+    // get quotation data for a validated quote request
+    pub async fn fetch_quotes(
+        &self,
+        request: &QuoteRequest,
+    ) -> Result<Array1<QuoteItem>, YahooErr> {
+        let mut query = vec![("interval", request.interval.to_string())];
+        match request.span {
+            Span::Dates(start, end) => {
+                query.push(("period1", start.unix_timestamp().to_string()));
+                query.push(("period2", end.unix_timestamp().to_string()));
+            }
+            Span::Range(range) => query.push(("range", range.to_string())),
         }
+        convert_chart(self.fetch_chart(&request.symbol, &query).await?)
+    }
 
-        // default case: get data for current trading day
-        convert_to_quoteitem(
-            self.provider
-                .get_quote_range(symbol, interval, "1d")
-                .await
-                .map_err(|err| YahooErr::FetchFailed(err.to_string()))?
-                .quotes()
-                .map_err(|err| {
-                    if err.to_string().contains("EOF") || err.to_string().contains("empty") {
-                        YahooErr::EmptyDataSet
-                    } else {
-                        YahooErr::InvalidJson
-                    }
-                })?,
-        )
-        .map_err(|e| YahooErr::DataInconsistency(e.to_string()))
+    // fetch yahoo! finance chart for a symbol, api errors (e.g. unknown symbol) are reported with their http status
+    async fn fetch_chart(
+        &self,
+        symbol: &str,
+        query: &[(&str, String)],
+    ) -> Result<YChartData, YahooErr> {
+        let response = self
+            .client
+            .get(format!("{}/v8/finance/chart/{}", self.base_url, symbol))
+            .query(query)
+            .send()
+            .await?;
+        let status = response.status();
+        let body = response.text().await?;
+
+        let chart = match serde_json::from_str::<YChartResponse>(&body) {
+            Ok(r) => r.chart,
+            // error pages are not always json, report the http status instead
+            Err(_) if !status.is_success() => {
+                return Err(YahooErr::HttpStatus(
+                    status.as_u16(),
+                    format!("{} returned {}", symbol, status),
+                ))
+            }
+            Err(err) => return Err(err.into()),
+        };
+        if let Some(err) = chart.error {
+            return Err(YahooErr::HttpStatus(
+                status.as_u16(),
+                format!("{}: {}", err.code, err.description),
+            ));
+        }
+        chart
+            .result
+            .and_then(|r| r.into_iter().next())
+            .ok_or(YahooErr::EmptyDataSet)
     }
 
-    // get quotation data for multiple assets query
+    // get quotation data for multiple assets query, using the default batch options
     pub async fn get_multiple_quotes(
         &self,
         tickers: Vec<&str>,
@@ -169,140 +207,594 @@ impl Yahoo {
         period: Option<&str>,
         interval: Option<&str>,
     ) -> Result<Array1<MultiQuoteItem>, YahooErr> {
-        // initialize results hashmap container
-        let mut r: Vec<(String, Vec<QuoteItem>)> = Vec::new();
-
-        // loop through tickers and fetch quotes data
-        for t in tickers {
-            r.push((
-                t.to_string(),
-                self.get_quotes(t, start_date, end_date, period, interval)
-                    .await?
-                    .into_iter()
-                    .map(|mut q| {
-                        if let Ok(date) = datetime_to_date(q.datetime.clone()) {
-                            q.datetime = date;
-                        }
-                        q
-                    })
-                    .collect(),
-            ));
+        self.get_multiple_quotes_with(
+            tickers,
+            start_date,
+            end_date,
+            period,
+            interval,
+            &BatchConfig::default(),
+        )
+        .await
+    }
+
+    // get quotation data for multiple assets query with the given concurrency and retry options
+    pub async fn get_multiple_quotes_with(
+        &self,
+        tickers: Vec<&str>,
+        start_date: Option<&str>,
+        end_date: Option<&str>,
+        period: Option<&str>,
+        interval: Option<&str>,
+        config: &BatchConfig,
+    ) -> Result<Array1<MultiQuoteItem>, YahooErr> {
+        // fetch tickers concurrently, the first failing ticker fails the whole query
+        let batch = get_quotes_batch(
+            self, &tickers, start_date, end_date, period, interval, config,
+        )
+        .await;
+        if let Some((_, e)) = batch.errors.into_iter().next() {
+            return Err(e);
         }
 
-        // transform the data into format
-        let mut m_quotes: Vec<MultiQuoteItem> = Vec::new();
+        Ok(group_by_date(batch.quotes))
+    }
+
+    // get quotation data for multiple assets query, reporting failed tickers instead of aborting
+    pub async fn get_multiple_quotes_batch(
+        &self,
+        tickers: Vec<&str>,
+        start_date: Option<&str>,
+        end_date: Option<&str>,
+        period: Option<&str>,
+        interval: Option<&str>,
+        config: &BatchConfig,
+    ) -> BatchQuotes<YahooErr> {
+        get_quotes_batch(
+            self, &tickers, start_date, end_date, period, interval, config,
+        )
+        .await
+    }
+
+    // get latest quotation for an asset
+    pub async fn get_latest_quote(&self, ticker: &str) -> Result<f64, YahooErr> {
+        let request = QuoteRequest {
+            symbol: ticker.to_string(),
+            interval: Interval::OneDay,
+            span: Span::Range(Range::OneMonth),
+        };
+        self.fetch_quotes(&request)
+            .await?
+            .last()
+            .map(|q| q.close)
+            .ok_or(YahooErr::EmptyDataSet)
+    }
 
-        // create a map to group quotes by date
-        let mut date_map: HashMap<String, HashMap<String, f64>> = HashMap::new();
+    // get asset options data
+    pub async fn get_options(
+        &self,
+        ticker: &str,
+        option_type: OptionType,
+    ) -> Result<Array1<OptionContract>, YahooErr> {
+        let session = self.session().await?;
+        let nearest = self
+            .fetch_options(ticker, None, &session)
+            .await?
+            .options
+            .into_iter()
+            .next()
+            .ok_or(YahooErr::EmptyDataSet)?;
+        Ok(Array1::from_vec(match option_type {
+            OptionType::Call => nearest.calls,
+            OptionType::Put => nearest.puts,
+        }))
+    }
 
-        for (ticker, quotes) in r {
-            for quote in quotes {
-                let date = quote.datetime.clone();
-                let adjclose = quote.adjclose;
+    // get asset full option chain (calls and puts for every expiration) with underlying quote
+    pub async fn get_option_chain(&self, ticker: &str) -> Result<OptionChain, YahooErr> {
+        let session = self.session().await?;
 
-                let prices = date_map.entry(date).or_default();
-                prices.insert(ticker.clone(), adjclose);
+        // first request returns nearest expiry contracts and the list of all expirations
+        let first = self.fetch_options(ticker, None, &session).await?;
+        let mut options = first.options;
+        for ts in &first.expiration_dates {
+            if options.iter().any(|o| o.expiration_date == *ts) {
+                continue;
             }
+            options.extend(
+                self.fetch_options(ticker, Some(*ts), &session)
+                    .await?
+                    .options,
+            );
         }
 
-        // convert the date_map into a vector of DynamicQuoteItem
-        for (date, prices) in date_map {
-            m_quotes.push(MultiQuoteItem { date, prices });
+        let mut expiries = options
+            .into_iter()
+            .map(|o| {
+                Ok(OptionExpiry {
+                    expiration: timestamp_to_datetime(o.expiration_date)?.date(),
+                    calls: Array1::from_vec(o.calls),
+                    puts: Array1::from_vec(o.puts),
+                })
+            })
+            .collect::<Result<Vec<OptionExpiry>, YahooErr>>()?;
+        expiries.sort_by_key(|e| e.expiration);
+
+        Ok(OptionChain {
+            underlying_symbol: first.underlying_symbol,
+            underlying_price: first.quote.as_ref().and_then(|q| q.regular_market_price),
+            currency: first.quote.and_then(|q| q.currency),
+            expiries,
+        })
+    }
+
+    // fetch yahoo! finance options for a single expiration (nearest when `expiration` is none)
+    async fn fetch_options(
+        &self,
+        ticker: &str,
+        expiration: Option<i64>,
+        session: &YSession,
+    ) -> Result<YOptionsData, YahooErr> {
+        let mut query = vec![("crumb", session.crumb.clone())];
+        if let Some(ts) = expiration {
+            query.push(("date", ts.to_string()));
         }
+        let body = self
+            .client
+            .get(format!("{}/v7/finance/options/{}", self.base_url, ticker))
+            .query(&query)
+            .header(COOKIE, &session.cookie)
+            .send()
+            .await?
+            .error_for_status()
+            .map_err(status_error)?
+            .text()
+            .await?;
 
-        // sort the dynamic_quotes by date (if needed)
-        m_quotes.sort_by(|a, b| a.date.cmp(&b.date));
+        serde_json::from_str::<YOptionsResponse>(&body)?
+            .option_chain
+            .result
+            .into_iter()
+            .next()
+            .ok_or(YahooErr::EmptyDataSet)
+    }
 
-        Ok(Array1::from_vec(m_quotes))
+    // get company metadata (sector, industry, valuation ratios, ...) for an asset
+    pub async fn get_quote_summary(&self, ticker: &str) -> Result<QuoteSummary, YahooErr> {
+        let session = self.session().await?;
+        self.fetch_quote_summary(ticker, &session).await
     }
 
-    // get latest quotation for an asset
-    pub async fn get_latest_quote(&self, ticker: &str) -> Result<f64, YahooErr> {
-        Ok(round_to_three(
-            self.provider
-                .get_latest_quotes(ticker, "1d")
-                .await
-                .map_err(|err| YahooErr::FetchFailed(err.to_string()))?
-                .last_quote()
-                .map_err(|e| YahooErr::DataInconsistency(e.to_string()))?
-                .close,
+    // get company metadata for multiple assets concurrently, a failing ticker does not abort the batch
+    pub async fn get_quote_summaries(
+        &self,
+        tickers: &[&str],
+        config: &BatchConfig,
+    ) -> Result<BatchSummaries<YahooErr>, YahooErr> {
+        let session = self.session().await?;
+        let (summaries, errors) = batch_requests(
+            tickers,
+            config,
+            |e| self.is_transient(e),
+            |t| self.fetch_quote_summary(t, &session),
+        )
+        .await;
+        Ok(BatchSummaries {
+            summaries: summaries.into_iter().map(|(_, s)| s).collect(),
+            errors,
+        })
+    }
+
+    async fn fetch_quote_summary(
+        &self,
+        ticker: &str,
+        session: &YSession,
+    ) -> Result<QuoteSummary, YahooErr> {
+        let body = self
+            .client
+            .get(format!(
+                "{}/v10/finance/quoteSummary/{}",
+                self.base_url, ticker
+            ))
+            .query(&[("modules", YSUMMARY_MODULES), ("crumb", &session.crumb)])
+            .header(COOKIE, &session.cookie)
+            .send()
+            .await?
+            .error_for_status()
+            .map_err(status_error)?
+            .text()
+            .await?;
+
+        serde_json::from_str::<YSummaryResponse>(&body)?
+            .quote_summary
+            .result
+            .and_then(|r| r.into_iter().next())
+            .map(|data| data.into_summary(ticker))
+            .ok_or(YahooErr::EmptyDataSet)
+    }
+
+    // get yahoo! finance session cookie and crumb required by direct api requests
+    async fn session(&self) -> Result<YSession, YahooErr> {
+        // cookie endpoint answers with an error status but still sets the session cookie
+        let cookie = self
+            .client
+            .get(&self.cookie_url)
+            .send()
+            .await?
+            .headers()
+            .get_all(SET_COOKIE)
+            .iter()
+            .filter_map(|c| c.to_str().ok()?.split(';').next().map(str::to_string))
+            .collect::<Vec<String>>()
+            .join("; ");
+        let crumb = self
+            .client
+            .get(format!("{}/v1/test/getcrumb", self.base_url))
+            .header(COOKIE, &cookie)
+            .send()
+            .await?
+            .error_for_status()
+            .map_err(status_error)?
+            .text()
+            .await?;
+        Ok(YSession { cookie, crumb })
+    }
+
+    // get dividends paid by an asset in a date range, sorted by ex-dividend date
+    pub async fn get_dividends(
+        &self,
+        symbol: &str,
+        start_date: &str,
+        end_date: &str,
+    ) -> Result<Array1<DividendItem>, YahooErr> {
+        let (events, timezone) = self.get_events(symbol, start_date, end_date).await?;
+        let mut dividends = events
+            .dividends
+            .into_values()
+            .map(|d| {
+                Ok(DividendItem {
+                    date: timestamp_to_exchange_dt(d.date, &timezone)?.date(),
+                    amount: d.amount,
+                })
+            })
+            .collect::<Result<Vec<DividendItem>, YahooErr>>()?;
+        dividends.sort_by_key(|d| d.date);
+        Ok(Array1::from_vec(dividends))
+    }
+
+    // get stock splits of an asset in a date range, sorted by date
+    pub async fn get_splits(
+        &self,
+        symbol: &str,
+        start_date: &str,
+        end_date: &str,
+    ) -> Result<Array1<SplitItem>, YahooErr> {
+        let (events, timezone) = self.get_events(symbol, start_date, end_date).await?;
+        let mut splits = events
+            .splits
+            .into_values()
+            .map(|s| {
+                Ok(SplitItem {
+                    date: timestamp_to_exchange_dt(s.date, &timezone)?.date(),
+                    numerator: s.numerator,
+                    denominator: s.denominator,
+                })
+            })
+            .collect::<Result<Vec<SplitItem>, YahooErr>>()?;
+        splits.sort_by_key(|s| s.date);
+        Ok(Array1::from_vec(splits))
+    }
+
+    // fetch daily history with corporate events for a date range, returns events and exchange timezone
+    async fn get_events(
+        &self,
+        symbol: &str,
+        start_date: &str,
+        end_date: &str,
+    ) -> Result<(YEvents, String), YahooErr> {
+        let start_dt = parse_date(start_date, DateType::Start)?;
+        let end_dt = parse_date(end_date, DateType::End)?;
+        let query = [
+            ("interval", Interval::OneDay.to_string()),
+            ("period1", start_dt.unix_timestamp().to_string()),
+            ("period2", end_dt.unix_timestamp().to_string()),
+            ("events", "div|split".to_string()),
+        ];
+        let chart = self.fetch_chart(symbol, &query).await?;
+        Ok((
+            chart.events.unwrap_or_default(),
+            chart.meta.exchange_timezone_name,
         ))
     }
 
-    // get asset options data
-    pub async fn get_options(
+    // search assets by name or ticker, results keep the yahoo! finance connector models
+    #[deprecated(note = "use `Yahoo::search`, which returns `SearchItem`s")]
+    pub async fn search_asset(self, name: &str) -> Result<YSearchResult, YahooErr> {
+        let body = self.fetch_search(name).await?;
+        Ok(YSearchResult::from_opt(&serde_json::from_str::<
+            YSearchResultOpt,
+        >(&body)?))
+    }
+
+    // search assets by name or ticker
+    pub async fn search(&self, name: &str) -> Result<Array1<SearchItem>, YahooErr> {
+        let body = self.fetch_search(name).await?;
+        Ok(Array1::from_vec(
+            serde_json::from_str::<YSearchResponse>(&body)?.quotes,
+        ))
+    }
+
+    async fn fetch_search(&self, name: &str) -> Result<String, YahooErr> {
+        Ok(self
+            .client
+            .get(format!("{}/v1/finance/search", self.base_url))
+            .query(&[("q", name), ("newsCount", "0")])
+            .send()
+            .await?
+            .error_for_status()
+            .map_err(status_error)?
+            .text()
+            .await?)
+    }
+}
+
+// yahoo! finance session used by direct api requests
+struct YSession {
+    cookie: String,
+    crumb: String,
+}
+
+// yahoo! finance chart endpoint response models
+#[derive(Deserialize)]
+struct YChartResponse {
+    chart: YChart,
+}
+
+#[derive(Deserialize)]
+struct YChart {
+    result: Option<Vec<YChartData>>,
+    error: Option<YApiError>,
+}
+
+#[derive(Deserialize)]
+struct YApiError {
+    code: String,
+    description: String,
+}
+
+#[derive(Deserialize)]
+struct YChartData {
+    meta: YChartMeta,
+    #[serde(default)]
+    timestamp: Vec<u64>,
+    events: Option<YEvents>,
+    indicators: YIndicators,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct YChartMeta {
+    exchange_timezone_name: String,
+}
+
+#[derive(Deserialize, Default)]
+struct YEvents {
+    #[serde(default)]
+    dividends: HashMap<String, YDividend>,
+    #[serde(default)]
+    splits: HashMap<String, YSplit>,
+}
+
+#[derive(Deserialize)]
+struct YDividend {
+    amount: f64,
+    date: u64,
+}
+
+#[derive(Deserialize)]
+struct YSplit {
+    date: u64,
+    numerator: f64,
+    denominator: f64,
+}
+
+#[derive(Deserialize)]
+struct YIndicators {
+    #[serde(default)]
+    quote: Vec<YQuoteBars>,
+    adjclose: Option<Vec<YAdjClose>>,
+}
+
+#[derive(Deserialize)]
+struct YQuoteBars {
+    #[serde(default)]
+    open: Vec<Option<f64>>,
+    #[serde(default)]
+    high: Vec<Option<f64>>,
+    #[serde(default)]
+    low: Vec<Option<f64>>,
+    #[serde(default)]
+    close: Vec<Option<f64>>,
+    #[serde(default)]
+    volume: Vec<Option<u64>>,
+}
+
+#[derive(Deserialize)]
+struct YAdjClose {
+    #[serde(default)]
+    adjclose: Vec<Option<f64>>,
+}
+
+// yahoo! finance search endpoint response model
+#[derive(Deserialize)]
+struct YSearchResponse {
+    #[serde(default)]
+    quotes: Vec<SearchItem>,
+}
+
+// yahoo! finance options endpoint response models
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct YOptionsResponse {
+    option_chain: YOptionsResult,
+}
+
+#[derive(Deserialize)]
+struct YOptionsResult {
+    result: Vec<YOptionsData>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct YOptionsData {
+    underlying_symbol: String,
+    #[serde(default)]
+    expiration_dates: Vec<i64>,
+    quote: Option<YOptionsQuote>,
+    #[serde(default)]
+    options: Vec<YOptionsExpiry>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct YOptionsQuote {
+    regular_market_price: Option<f64>,
+    currency: Option<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct YOptionsExpiry {
+    expiration_date: i64,
+    #[serde(default)]
+    calls: Vec<OptionContract>,
+    #[serde(default)]
+    puts: Vec<OptionContract>,
+}
+
+// yahoo! finance as market data provider
+impl MarketDataProvider for Yahoo {
+    type Error = YahooErr;
+
+    async fn get_quotes(
+        &self,
+        symbol: &str,
+        start_date: Option<&str>,
+        end_date: Option<&str>,
+        period: Option<&str>,
+        interval: Option<&str>,
+    ) -> Result<Array1<QuoteItem>, YahooErr> {
+        Yahoo::get_quotes(self, symbol, start_date, end_date, period, interval).await
+    }
+
+    async fn get_multiple_quotes(
+        &self,
+        tickers: Vec<&str>,
+        start_date: Option<&str>,
+        end_date: Option<&str>,
+        period: Option<&str>,
+        interval: Option<&str>,
+    ) -> Result<Array1<MultiQuoteItem>, YahooErr> {
+        Yahoo::get_multiple_quotes(self, tickers, start_date, end_date, period, interval).await
+    }
+
+    async fn get_latest_quote(&self, ticker: &str) -> Result<f64, YahooErr> {
+        Yahoo::get_latest_quote(self, ticker).await
+    }
+
+    async fn get_options(
         &self,
         ticker: &str,
         option_type: OptionType,
     ) -> Result<Array1<OptionContract>, YahooErr> {
-        let r = self
-            .provider
-            .search_options(ticker)
-            .await
-            .map_err(|e| YahooErr::FetchFailed(e.to_string()))?;
-
-        if r.option_chain.result.is_empty() {
-            return Err(YahooErr::EmptyDataSet);
+        Yahoo::get_options(self, ticker, option_type).await
+    }
+
+    // only rate limits, server errors and connection failures are worth retrying,
+    // anything else (e.g. an unknown symbol) fails the same way on every attempt
+    fn is_transient(&self, error: &YahooErr) -> bool {
+        match error {
+            YahooErr::HttpStatus(status, _) => *status == 429 || (500..600).contains(status),
+            YahooErr::RequestFailed(err) => err.is_connect() || err.is_timeout(),
+            _ => false,
         }
+    }
+}
+
+// helper function to keep the http status of a failed yahoo! finance response
+fn status_error(err: reqwest::Error) -> YahooErr {
+    YahooErr::HttpStatus(err.status().map_or(0, |s| s.as_u16()), err.to_string())
+}
+
+// helper function to convert yahoo! finance chart into vector of quotes in exchange local time
+// bars with missing prices (e.g. trading halts) are skipped, missing adjusted close falls back to close
+fn convert_chart(chart: YChartData) -> Result<Array1<QuoteItem>, YahooErr> {
+    let timezone = chart.meta.exchange_timezone_name;
+    let bars = match chart.indicators.quote.into_iter().next() {
+        Some(bars) => bars,
+        None => return Err(YahooErr::EmptyDataSet),
+    };
+    let adjclose = chart
+        .indicators
+        .adjclose
+        .and_then(|a| a.into_iter().next())
+        .map(|a| a.adjclose)
+        .unwrap_or_default();
+    let at = |v: &[Option<f64>], i: usize| v.get(i).copied().flatten();
 
-        let options = &r.option_chain.result[0].options[0];
-        let options = match option_type {
-            OptionType::Call => &options.calls,
-            OptionType::Put => &options.puts,
+    let mut quotes: Vec<QuoteItem> = Vec::new();
+    for (i, ts) in chart.timestamp.into_iter().enumerate() {
+        let (Some(open), Some(high), Some(low), Some(close)) = (
+            at(&bars.open, i),
+            at(&bars.high, i),
+            at(&bars.low, i),
+            at(&bars.close, i),
+        ) else {
+            continue;
         };
-        convert_to_optioncontract(options)
+        quotes.push(QuoteItem {
+            datetime: timestamp_to_exchange_dt(ts, &timezone)?,
+            timezone: timezone.clone(),
+            open,
+            high,
+            low,
+            close,
+            adjclose: at(&adjclose, i).unwrap_or(close),
+            volume: bars.volume.get(i).copied().flatten().unwrap_or(0),
+        });
     }
 
-    // search asset
-    pub async fn search_asset(self, name: &str) -> Result<YSearchResult, YahooErr> {
-        self.provider
-            .search_ticker(name)
-            .await
-            .map_err(|e| YahooErr::FetchFailed(e.to_string()))
+    if quotes.is_empty() {
+        return Err(YahooErr::EmptyDataSet);
     }
+    Ok(Array1::from_vec(quotes))
 }
 
-// helper function to convert yaho! finance response into vector
-fn convert_to_quoteitem(quotes: Vec<Quote>) -> Result<Array1<QuoteItem>, Box<dyn Error>> {
-    Ok(Array1::from_iter(
-        quotes
-            .into_iter()
-            .map(|q| {
-                let datetime = timestamp_to_localdt(q.timestamp)?;
-                Ok(QuoteItem {
-                    datetime,
-                    open: round_to_three(q.open),
-                    high: round_to_three(q.high),
-                    low: round_to_three(q.low),
-                    close: round_to_three(q.close),
-                    adjclose: round_to_three(q.adjclose),
-                    volume: q.volume,
-                })
-            })
-            .collect::<Result<Vec<QuoteItem>, Box<dyn Error>>>()?,
-    ))
-}
+// helper function to group single asset quotes by date into multiquotes (adjusted close prices)
+pub(crate) fn group_by_date(r: Vec<(String, Array1<QuoteItem>)>) -> Array1<MultiQuoteItem> {
+    // transform the data into format
+    let mut m_quotes: Vec<MultiQuoteItem> = Vec::new();
+
+    // create a map to group quotes by exchange local date
+    let mut date_map: HashMap<Date, HashMap<String, f64>> = HashMap::new();
+
+    for (ticker, quotes) in r {
+        for quote in quotes {
+            let date = quote.datetime.date();
+            let adjclose = quote.adjclose;
 
-fn convert_to_optioncontract(
-    options: &[YOptionContract],
-) -> Result<Array1<OptionContract>, YahooErr> {
-    Ok(Array1::from_iter(options.iter().map(|option| {
-        OptionContract {
-            contract_symbol: option.contract_symbol.clone(),
-            strike: option.strike,
-            currency: option.currency.clone(),
-            last_price: option.last_price,
-            change: option.change,
-            percent_change: option.percent_change,
-            volume: option.volume,
-            open_interest: option.open_interest,
-            bid: option.bid,
-            ask: option.ask,
-            contract_size: option.contract_size.clone(),
-            expiration: option.expiration,
-            last_trade_date: option.last_trade_date,
-            implied_volatility: option.implied_volatility,
-            in_the_money: option.in_the_money,
+            let prices = date_map.entry(date).or_default();
+            prices.insert(ticker.clone(), adjclose);
         }
-    })))
+    }
+
+    // convert the date_map into a vector of MultiQuoteItem
+    for (date, prices) in date_map {
+        m_quotes.push(MultiQuoteItem { date, prices });
+    }
+
+    // sort the multiquotes by date
+    m_quotes.sort_by_key(|q| q.date);
+
+    Array1::from_vec(m_quotes)
 }
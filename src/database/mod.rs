@@ -1,2 +1,19 @@
+#[cfg(not(any(feature = "mysql", feature = "postgres", feature = "sqlite")))]
+compile_error!("enable at least one database backend feature: mysql, postgres or sqlite");
+
+// run the same sqlx expression against the pool of whichever backend is connected
+macro_rules! on_pool {
+    ($pool:expr, $p:ident => $body:expr) => {
+        match $pool {
+            #[cfg(feature = "mysql")]
+            $crate::database::connection::DbPool::MySql($p) => $body,
+            #[cfg(feature = "postgres")]
+            $crate::database::connection::DbPool::Postgres($p) => $body,
+            #[cfg(feature = "sqlite")]
+            $crate::database::connection::DbPool::Sqlite($p) => $body,
+        }
+    };
+}
+
 pub mod connection;
 pub mod queries;
@@ -1,7 +1,28 @@
+use super::connection::{Backend, DbPool};
+use crate::commons::date::{timestamp_to_exchange_dt, DateTimeError};
+use crate::data::yahoo::QuoteItem;
 use chrono::NaiveDate;
+use ndarray::Array1;
 use serde::Serialize;
-use sqlx::{types::Decimal, Error as SqlxError, FromRow, MySqlPool};
+#[cfg(feature = "mysql")]
+use sqlx::mysql::MySqlRow;
+#[cfg(feature = "postgres")]
+use sqlx::postgres::PgRow;
+#[cfg(feature = "sqlite")]
+use sqlx::sqlite::SqliteRow;
+#[cfg(feature = "mysql")]
+use sqlx::MySql;
+#[cfg(feature = "postgres")]
+use sqlx::Postgres;
+#[cfg(feature = "sqlite")]
+use sqlx::Sqlite;
+use sqlx::{
+    error::BoxDynError, types::Decimal, Database, Encode, Error as SqlxError, FromRow,
+    QueryBuilder, Row, Type,
+};
+use std::{collections::HashSet, fmt, str::FromStr};
 use thiserror::Error;
+use time::{Date, Duration, OffsetDateTime};
 
 #[derive(Debug, Error)]
 pub enum DbQueryError {
@@ -9,9 +30,22 @@ pub enum DbQueryError {
     QueryError(#[from] SqlxError),
     #[error("Query returned empty result.")]
     Notfound,
+    #[error("Invalid stored datetime: {0}")]
+    DateTime(#[from] DateTimeError),
+    #[error("Unknown transaction kind '{0}'")]
+    UnknownTransactionKind(String),
+    #[error("Volume {2} of {0} at {1} does not fit a 64 bit integer column")]
+    VolumeOutOfRange(String, OffsetDateTime, u64),
+    #[error("Invalid {field} of position {ticker}: {source}")]
+    InvalidPosition {
+        ticker: String,
+        field: String,
+        #[source]
+        source: BoxDynError,
+    },
 }
 
-#[derive(FromRow, Serialize, Debug)]
+#[derive(Serialize, Debug)]
 pub struct VWPortfolio {
     pub ticker: String,
     pub quantity: Decimal,
@@ -21,23 +55,664 @@ pub struct VWPortfolio {
     pub buy_value: Decimal,
     pub market_price: Decimal,
     pub market_value: Decimal,
-    #[sqlx(rename = "PL")]
     pub pl: Decimal,
-    #[sqlx(rename = "PL_pct")]
     pub pl_pct: Decimal,
 }
 
-pub async fn portfolio_tickers(pool: &MySqlPool) -> Result<Vec<String>, DbQueryError> {
-    let r = sqlx::query!("SELECT ticker FROM vw_portfolio")
-        .fetch_all(pool)
-        .await?;
+impl VWPortfolio {
+    // recompute values and profit and loss from quantity, buy and market price in decimal arithmetic
+    fn exact_values(self) -> Result<Self, DbQueryError> {
+        let overflow = |field: &str| DbQueryError::InvalidPosition {
+            ticker: self.ticker.clone(),
+            field: field.to_string(),
+            source: "value overflows the decimal range".into(),
+        };
+        let buy_value = self
+            .quantity
+            .checked_mul(self.buy_price)
+            .ok_or_else(|| overflow("buy_value"))?;
+        let market_value = self
+            .quantity
+            .checked_mul(self.market_price)
+            .ok_or_else(|| overflow("market_value"))?;
+        let pl = market_value
+            .checked_sub(buy_value)
+            .ok_or_else(|| overflow("pl"))?;
+        let pl_pct = if self.buy_price.is_zero() {
+            Decimal::ZERO
+        } else {
+            (self.market_price - self.buy_price)
+                .checked_div(self.buy_price)
+                .ok_or_else(|| overflow("pl_pct"))?
+        };
+        Ok(Self {
+            buy_value,
+            market_value,
+            pl,
+            pl_pct,
+            ..self
+        })
+    }
+}
+
+// decimal column decoding, native on mysql and postgres
+// sqlite has no decimal type: columns are read as text and parsed, decimals stored as text
+// come back exact, computed (integer or floating point) values as sqlite renders them
+trait DecimalRow: Row {
+    fn decimal(&self, column: &str) -> Result<Decimal, SqlxError>;
+}
+
+#[cfg(feature = "mysql")]
+impl DecimalRow for MySqlRow {
+    fn decimal(&self, column: &str) -> Result<Decimal, SqlxError> {
+        self.try_get(column)
+    }
+}
+
+#[cfg(feature = "postgres")]
+impl DecimalRow for PgRow {
+    fn decimal(&self, column: &str) -> Result<Decimal, SqlxError> {
+        self.try_get(column)
+    }
+}
+
+#[cfg(feature = "sqlite")]
+impl DecimalRow for SqliteRow {
+    fn decimal(&self, column: &str) -> Result<Decimal, SqlxError> {
+        // unchecked: view columns hold integer or real values, sqlite converts them into text
+        let value: String = self.try_get_unchecked(column)?;
+        Decimal::from_str(&value)
+            .or_else(|_| Decimal::from_scientific(&value))
+            .map_err(|e| SqlxError::ColumnDecode {
+                index: column.to_string(),
+                source: Box::new(e),
+            })
+    }
+}
+
+// decimal parameter binding, native on mysql and postgres
+// sqlite has no decimal type: values are bound as text, stored verbatim in text affinity columns
+trait DecimalBind: Database {
+    fn push_decimal(qb: &mut QueryBuilder<'_, Self>, value: Decimal);
+}
+
+#[cfg(feature = "mysql")]
+impl DecimalBind for MySql {
+    fn push_decimal(qb: &mut QueryBuilder<'_, Self>, value: Decimal) {
+        qb.push_bind(value);
+    }
+}
+
+#[cfg(feature = "postgres")]
+impl DecimalBind for Postgres {
+    fn push_decimal(qb: &mut QueryBuilder<'_, Self>, value: Decimal) {
+        qb.push_bind(value);
+    }
+}
+
+#[cfg(feature = "sqlite")]
+impl DecimalBind for Sqlite {
+    fn push_decimal(qb: &mut QueryBuilder<'_, Self>, value: Decimal) {
+        qb.push_bind(value.to_string());
+    }
+}
+
+// decoding failure of a `vw_portfolio` column, carried through sqlx by `FromRow` and
+// surfaced by `portfolio_table` as `DbQueryError::InvalidPosition`
+#[derive(Debug, Error)]
+#[error("position {ticker}: invalid {field}: {source}")]
+struct PositionDecodeError {
+    ticker: String,
+    field: String,
+    #[source]
+    source: BoxDynError,
+}
+
+// attach the ticker and field to the column decoding error of a position
+fn position_error(ticker: &str, field: &str, err: SqlxError) -> SqlxError {
+    let source = match err {
+        SqlxError::ColumnDecode { source, .. } => source,
+        err => Box::new(err),
+    };
+    SqlxError::ColumnDecode {
+        index: field.to_string(),
+        source: Box::new(PositionDecodeError {
+            ticker: ticker.to_string(),
+            field: field.to_string(),
+            source,
+        }),
+    }
+}
+
+// unwrap the position decoding failures raised by `position_error`
+fn invalid_position(err: SqlxError) -> DbQueryError {
+    match err {
+        SqlxError::ColumnDecode { index, source } => {
+            match source.downcast::<PositionDecodeError>() {
+                Ok(e) => DbQueryError::InvalidPosition {
+                    ticker: e.ticker,
+                    field: e.field,
+                    source: e.source,
+                },
+                Err(source) => SqlxError::ColumnDecode { index, source }.into(),
+            }
+        }
+        err => err.into(),
+    }
+}
+
+// rows with decimal columns, decoded through `DecimalRow`
+macro_rules! decimal_from_row {
+    ($row:ty) => {
+        impl FromRow<'_, $row> for VWPortfolio {
+            fn from_row(row: &$row) -> Result<Self, SqlxError> {
+                let ticker: String = row.try_get("ticker")?;
+                let decimal = |field: &str| {
+                    row.decimal(field)
+                        .map_err(|e| position_error(&ticker, field, e))
+                };
+                Ok(Self {
+                    quantity: decimal("quantity")?,
+                    currency: row
+                        .try_get("currency")
+                        .map_err(|e| position_error(&ticker, "currency", e))?,
+                    buy_date: row
+                        .try_get("buy_date")
+                        .map_err(|e| position_error(&ticker, "buy_date", e))?,
+                    buy_price: decimal("buy_price")?,
+                    buy_value: decimal("buy_value")?,
+                    market_price: decimal("market_price")?,
+                    market_value: decimal("market_value")?,
+                    pl: decimal("pl")?,
+                    pl_pct: decimal("pl_pct")?,
+                    ticker,
+                })
+            }
+        }
+
+        impl FromRow<'_, $row> for Transaction {
+            fn from_row(row: &$row) -> Result<Self, SqlxError> {
+                Ok(Self {
+                    id: Some(row.try_get("id")?),
+                    trade_date: row.try_get("trade_date")?,
+                    kind: row.try_get::<String, _>("kind")?.parse().map_err(|e| {
+                        SqlxError::ColumnDecode {
+                            index: "kind".to_string(),
+                            source: Box::new(e),
+                        }
+                    })?,
+                    ticker: row.try_get("ticker")?,
+                    quantity: row.decimal("quantity")?,
+                    price: row.decimal("price")?,
+                    amount: row.decimal("amount")?,
+                    fees: row.decimal("fees")?,
+                    currency: row.try_get("currency")?,
+                    note: row.try_get("note")?,
+                })
+            }
+        }
+    };
+}
+
+#[cfg(feature = "mysql")]
+decimal_from_row!(MySqlRow);
+#[cfg(feature = "postgres")]
+decimal_from_row!(PgRow);
+#[cfg(feature = "sqlite")]
+decimal_from_row!(SqliteRow);
+
+pub async fn portfolio_tickers(pool: &DbPool) -> Result<Vec<String>, DbQueryError> {
+    let r: Vec<String> = on_pool!(pool, p => {
+        sqlx::query_scalar("SELECT ticker FROM vw_portfolio")
+            .fetch_all(p)
+            .await?
+    });
 
     if r.is_empty() {
         return Err(DbQueryError::Notfound);
     }
-    Ok(r.iter().map(|r| r.ticker.clone()).collect())
+    Ok(r)
+}
+
+// profit and loss columns are aliased lowercase: postgres folds unquoted identifiers, mysql keeps `PL`
+pub async fn portfolio_table(pool: &DbPool) -> Result<Vec<VWPortfolio>, DbQueryError> {
+    const QUERY: &str = "SELECT ticker, quantity, currency, buy_date, buy_price, buy_value, market_price, market_value, PL AS pl, PL_pct AS pl_pct FROM vw_portfolio";
+    let rows = on_pool!(pool, p => {
+        sqlx::query_as::<_, VWPortfolio>(QUERY)
+            .fetch_all(p)
+            .await
+            .map_err(invalid_position)?
+    });
+    // the sqlite view multiplies in floating point, only its text columns are exact
+    if pool.backend() == Backend::Sqlite {
+        return rows.into_iter().map(VWPortfolio::exact_values).collect();
+    }
+    Ok(rows)
+}
+
+// struct to model a row of the instruments table, price bars and positions reference it by ticker
+#[derive(Debug, Clone, PartialEq, FromRow)]
+pub struct Instrument {
+    pub ticker: String,
+    pub name: Option<String>,
+    pub currency: String,
+    pub exchange: Option<String>,
+    pub quote_type: Option<String>,
+}
+
+impl Instrument {
+    pub fn new(ticker: &str, currency: &str) -> Self {
+        Self {
+            ticker: ticker.to_string(),
+            name: None,
+            currency: currency.to_uppercase(),
+            exchange: None,
+            quote_type: None,
+        }
+    }
+}
+
+// stored price bar, timestamps are unix seconds
+#[derive(FromRow)]
+struct PriceRow {
+    ts: i64,
+    timezone: String,
+    open: f64,
+    high: f64,
+    low: f64,
+    close: f64,
+    adjclose: f64,
+    volume: i64,
 }
 
-pub async fn portfolio_table(pool: &MySqlPool) -> Result<Vec<VWPortfolio>, DbQueryError> {
-    Ok(sqlx::query_as::<_, VWPortfolio>("SELECT ticker, quantity, currency, buy_date, buy_price, buy_value, market_price, market_value, PL, PL_pct FROM vw_portfolio").fetch_all(pool).await?)
+// bind parameters per statement stay below the sqlite limit (32766)
+const UPSERT_CHUNK: usize = 1000;
+
+// insert an instrument or update its details when the ticker is already stored
+pub async fn upsert_instrument(pool: &DbPool, instrument: &Instrument) -> Result<(), DbQueryError> {
+    let upsert = on_conflict_update(
+        pool.backend(),
+        &["ticker"],
+        &["name", "currency", "exchange", "quote_type"],
+    );
+    on_pool!(pool, p => {
+        let mut qb = QueryBuilder::new(
+            "INSERT INTO instruments (ticker, name, currency, exchange, quote_type) ",
+        );
+        qb.push_values([instrument], |mut b, i| {
+            b.push_bind(i.ticker.clone())
+                .push_bind(i.name.clone())
+                .push_bind(i.currency.clone())
+                .push_bind(i.exchange.clone())
+                .push_bind(i.quote_type.clone());
+        });
+        qb.push(&upsert);
+        qb.build().execute(p).await?;
+    });
+    Ok(())
+}
+
+pub async fn get_instrument(pool: &DbPool, ticker: &str) -> Result<Instrument, DbQueryError> {
+    let r: Option<Instrument> = on_pool!(pool, p => {
+        let mut qb = QueryBuilder::new(
+            "SELECT ticker, name, currency, exchange, quote_type FROM instruments WHERE ticker = ",
+        );
+        qb.push_bind(ticker);
+        qb.build_query_as().fetch_optional(p).await?
+    });
+    r.ok_or(DbQueryError::Notfound)
+}
+
+// bulk insert price bars of a ticker, bars already stored for the same (ticker, datetime, interval)
+// are overwritten, the whole series is written in a single transaction
+// the instrument must be stored first (see `upsert_instrument`)
+pub async fn upsert_quotes(
+    pool: &DbPool,
+    ticker: &str,
+    interval: &str,
+    quotes: &Array1<QuoteItem>,
+) -> Result<(), DbQueryError> {
+    let upsert = on_conflict_update(
+        pool.backend(),
+        &["ticker", "ts", "bar_interval"],
+        &[
+            "timezone", "open", "high", "low", "close", "adjclose", "volume",
+        ],
+    );
+    // volumes are stored as signed integers, reject the whole batch before writing any bar
+    let quotes = quotes
+        .iter()
+        .map(|q| {
+            i64::try_from(q.volume)
+                .map(|volume| (q, volume))
+                .map_err(|_| {
+                    DbQueryError::VolumeOutOfRange(ticker.to_string(), q.datetime, q.volume)
+                })
+        })
+        .collect::<Result<Vec<(&QuoteItem, i64)>, DbQueryError>>()?;
+    on_pool!(pool, p => {
+        let mut tx = p.begin().await?;
+        for chunk in quotes.chunks(UPSERT_CHUNK) {
+            let mut qb = QueryBuilder::new(
+                "INSERT INTO prices (ticker, ts, bar_interval, timezone, open, high, low, close, adjclose, volume) ",
+            );
+            qb.push_values(chunk, |mut b, (q, volume)| {
+                b.push_bind(ticker)
+                    .push_bind(q.datetime.unix_timestamp())
+                    .push_bind(interval)
+                    .push_bind(q.timezone.clone())
+                    .push_bind(q.open)
+                    .push_bind(q.high)
+                    .push_bind(q.low)
+                    .push_bind(q.close)
+                    .push_bind(q.adjclose)
+                    .push_bind(*volume);
+            });
+            qb.push(&upsert);
+            qb.build().execute(&mut *tx).await?;
+        }
+        tx.commit().await?;
+    });
+    Ok(())
+}
+
+// stored price bars of a ticker whose exchange local date falls into [start, end], sorted by datetime
+pub async fn load_quotes(
+    pool: &DbPool,
+    ticker: &str,
+    interval: &str,
+    start: Date,
+    end: Date,
+) -> Result<Array1<QuoteItem>, DbQueryError> {
+    // utc window wide enough to hold every local date of the range in any timezone
+    let from = start.midnight().assume_utc() - Duration::days(1);
+    let to = end.midnight().assume_utc() + Duration::days(2);
+    let rows: Vec<PriceRow> = on_pool!(pool, p => {
+        let mut qb = QueryBuilder::new(
+            "SELECT ts, timezone, open, high, low, close, adjclose, volume FROM prices WHERE ticker = ",
+        );
+        qb.push_bind(ticker)
+            .push(" AND bar_interval = ")
+            .push_bind(interval)
+            .push(" AND ts >= ")
+            .push_bind(from.unix_timestamp())
+            .push(" AND ts < ")
+            .push_bind(to.unix_timestamp())
+            .push(" ORDER BY ts");
+        qb.build_query_as().fetch_all(p).await?
+    });
+
+    let mut quotes = Vec::with_capacity(rows.len());
+    for r in rows {
+        let datetime = timestamp_to_exchange_dt(r.ts as u64, &r.timezone)?;
+        if start <= datetime.date() && datetime.date() <= end {
+            quotes.push(QuoteItem {
+                datetime,
+                timezone: r.timezone,
+                open: r.open,
+                high: r.high,
+                low: r.low,
+                close: r.close,
+                adjclose: r.adjclose,
+                volume: r.volume as u64,
+            });
+        }
+    }
+    Ok(Array1::from_vec(quotes))
+}
+
+// date ranges (inclusive) in [start, end] with no stored bar, for an incremental sync to fetch
+// a date is covered when at least one bar falls on it (exchange local date) and the day is over:
+// today's bar may be an in-progress session, so today (and any later date) is always missing
+// `closed_days` is the exchange calendar: those dates never count as missing and do not split a
+// range, e.g. with weekends closed a gap from friday to monday is a single range
+// no day is closed by default (24/7 instruments such as crypto), pass weekends and holidays
+// for exchange traded instruments, closed days missing from the calendar are reported as missing
+// on every call since there is no bar to store for them
+pub async fn missing_ranges(
+    pool: &DbPool,
+    ticker: &str,
+    interval: &str,
+    start: Date,
+    end: Date,
+    closed_days: &[Date],
+) -> Result<Vec<(Date, Date)>, DbQueryError> {
+    let last_closed = OffsetDateTime::now_utc()
+        .date()
+        .previous_day()
+        .unwrap_or(Date::MIN);
+    let covered: HashSet<Date> = load_quotes(pool, ticker, interval, start, end)
+        .await?
+        .iter()
+        .map(|q| q.datetime.date())
+        .filter(|d| *d <= last_closed)
+        .collect();
+    let closed_days: HashSet<&Date> = closed_days.iter().collect();
+
+    let mut ranges: Vec<(Date, Date)> = Vec::new();
+    let mut open: Option<(Date, Date)> = None;
+    let mut date = start;
+    while date <= end {
+        if !closed_days.contains(&date) {
+            if covered.contains(&date) {
+                ranges.extend(open.take());
+            } else {
+                open = Some((open.map_or(date, |(s, _)| s), date));
+            }
+        }
+        date = match date.next_day() {
+            Some(d) => d,
+            None => break,
+        };
+    }
+    ranges.extend(open);
+    Ok(ranges)
+}
+
+// ledger entry types
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TransactionKind {
+    Buy,
+    Sell,
+    Dividend,
+    Fee,
+    Deposit,
+    Withdrawal,
+    Split,
+}
+
+impl TransactionKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TransactionKind::Buy => "buy",
+            TransactionKind::Sell => "sell",
+            TransactionKind::Dividend => "dividend",
+            TransactionKind::Fee => "fee",
+            TransactionKind::Deposit => "deposit",
+            TransactionKind::Withdrawal => "withdrawal",
+            TransactionKind::Split => "split",
+        }
+    }
+}
+
+impl fmt::Display for TransactionKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for TransactionKind {
+    type Err = DbQueryError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "buy" => Ok(TransactionKind::Buy),
+            "sell" => Ok(TransactionKind::Sell),
+            "dividend" => Ok(TransactionKind::Dividend),
+            "fee" => Ok(TransactionKind::Fee),
+            "deposit" => Ok(TransactionKind::Deposit),
+            "withdrawal" => Ok(TransactionKind::Withdrawal),
+            "split" => Ok(TransactionKind::Split),
+            _ => Err(DbQueryError::UnknownTransactionKind(s.to_string())),
+        }
+    }
+}
+
+// struct to model a row of the transactions ledger, `id` is set by the database on insert
+// buy and sell use `quantity` and `price`, cash events (dividend, fee, deposit, withdrawal) use
+// `amount`, splits store the ratio (new shares per old share) in `quantity`
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Transaction {
+    pub id: Option<i64>,
+    pub trade_date: NaiveDate,
+    pub kind: TransactionKind,
+    pub ticker: Option<String>,
+    pub quantity: Decimal,
+    pub price: Decimal,
+    pub amount: Decimal,
+    pub fees: Decimal,
+    pub currency: String,
+    pub note: Option<String>,
+}
+
+impl Transaction {
+    // create new ledger entry with zero quantity, price, amount and fees
+    pub fn new(kind: TransactionKind, trade_date: NaiveDate, currency: &str) -> Self {
+        Self {
+            id: None,
+            trade_date,
+            kind,
+            ticker: None,
+            quantity: Decimal::ZERO,
+            price: Decimal::ZERO,
+            amount: Decimal::ZERO,
+            fees: Decimal::ZERO,
+            currency: currency.to_uppercase(),
+            note: None,
+        }
+    }
+
+    // create buy or sell entry of `quantity` shares at `price`
+    pub fn trade(
+        kind: TransactionKind,
+        ticker: &str,
+        trade_date: NaiveDate,
+        quantity: Decimal,
+        price: Decimal,
+        currency: &str,
+    ) -> Self {
+        Self {
+            ticker: Some(ticker.to_string()),
+            quantity,
+            price,
+            ..Self::new(kind, trade_date, currency)
+        }
+    }
+}
+
+const TRANSACTION_COLUMNS: &str =
+    "id, trade_date, kind, ticker, quantity, price, amount, fees, currency, note";
+
+// store a ledger entry, returns the id assigned by the database
+pub async fn insert_transaction(
+    pool: &DbPool,
+    transaction: &Transaction,
+) -> Result<i64, DbQueryError> {
+    let id = match pool {
+        #[cfg(feature = "mysql")]
+        DbPool::MySql(p) => {
+            let mut qb = QueryBuilder::new("");
+            push_insert_transaction(&mut qb, transaction);
+            qb.build().execute(p).await?.last_insert_id() as i64
+        }
+        #[cfg(feature = "postgres")]
+        DbPool::Postgres(p) => {
+            let mut qb = QueryBuilder::new("");
+            push_insert_transaction(&mut qb, transaction);
+            qb.push(" RETURNING id");
+            qb.build_query_scalar().fetch_one(p).await?
+        }
+        #[cfg(feature = "sqlite")]
+        DbPool::Sqlite(p) => {
+            let mut qb = QueryBuilder::new("");
+            push_insert_transaction(&mut qb, transaction);
+            qb.build().execute(p).await?.last_insert_rowid()
+        }
+    };
+    Ok(id)
+}
+
+// ledger entries sorted by trade date and insertion order, optionally of a single ticker
+pub async fn list_transactions(
+    pool: &DbPool,
+    ticker: Option<&str>,
+) -> Result<Vec<Transaction>, DbQueryError> {
+    Ok(on_pool!(pool, p => {
+        let mut qb = QueryBuilder::new(format!("SELECT {TRANSACTION_COLUMNS} FROM transactions"));
+        if let Some(t) = ticker {
+            qb.push(" WHERE ticker = ").push_bind(t);
+        }
+        qb.push(" ORDER BY trade_date, id");
+        qb.build_query_as().fetch_all(p).await?
+    }))
+}
+
+pub async fn delete_transaction(pool: &DbPool, id: i64) -> Result<(), DbQueryError> {
+    let deleted = on_pool!(pool, p => {
+        let mut qb = QueryBuilder::new("DELETE FROM transactions WHERE id = ");
+        qb.push_bind(id);
+        qb.build().execute(p).await?.rows_affected()
+    });
+    if deleted == 0 {
+        return Err(DbQueryError::Notfound);
+    }
+    Ok(())
+}
+
+// helper function to push the insert statement of a ledger entry, the id is left to the database
+fn push_insert_transaction<'a, DB: DecimalBind>(qb: &mut QueryBuilder<'a, DB>, t: &'a Transaction)
+where
+    NaiveDate: Encode<'a, DB> + Type<DB>,
+    &'a str: Encode<'a, DB> + Type<DB>,
+    Option<&'a str>: Encode<'a, DB> + Type<DB>,
+{
+    qb.push("INSERT INTO transactions (trade_date, kind, ticker, quantity, price, amount, fees, currency, note) VALUES (")
+        .push_bind(t.trade_date)
+        .push(", ")
+        .push_bind(t.kind.as_str())
+        .push(", ")
+        .push_bind(t.ticker.as_deref());
+    for value in [t.quantity, t.price, t.amount, t.fees] {
+        qb.push(", ");
+        DB::push_decimal(qb, value);
+    }
+    qb.push(", ")
+        .push_bind(t.currency.as_str())
+        .push(", ")
+        .push_bind(t.note.as_deref())
+        .push(")");
+}
+
+// helper function to build the backend specific clause updating `columns` on primary key conflict
+fn on_conflict_update(backend: Backend, key: &[&str], columns: &[&str]) -> String {
+    match backend {
+        Backend::MySql => format!(
+            " ON DUPLICATE KEY UPDATE {}",
+            columns
+                .iter()
+                .map(|c| format!("{c} = VALUES({c})"))
+                .collect::<Vec<String>>()
+                .join(", ")
+        ),
+        Backend::Postgres | Backend::Sqlite => format!(
+            " ON CONFLICT ({}) DO UPDATE SET {}",
+            key.join(", "),
+            columns
+                .iter()
+                .map(|c| format!("{c} = excluded.{c}"))
+                .collect::<Vec<String>>()
+                .join(", ")
+        ),
+    }
 }
@@ -0,0 +1,74 @@
+//! crate-wide error wrapping every module error
+use crate::commons::date::DateTimeError;
+#[cfg(feature = "arrow")]
+use crate::data::arrow::ArrowError;
+#[cfg(feature = "polars")]
+use crate::data::polars::DataFrameError;
+use crate::data::{
+    alphavantage::AlphaVantageErr, cache::CacheError, csv::CsvError, error::YahooErr, fx::FxError,
+    panel::PanelError, resample::ResampleError,
+};
+use crate::database::{connection::DbError, queries::DbQueryError};
+use crate::portfolio::portfolio::PortfolioError;
+use crate::quantitative::{
+    error::{EmptyInput, MinMaxErr, MultiInputErr, QuantileErr, ShapeMismatch},
+    matrix::MatrixError,
+    returns::ReturnsError,
+    rootfinding::RootFindingError,
+    sequence::SequenceErr,
+};
+use thiserror::Error;
+
+// top-level error, every module error converts into it so `?` works across modules
+// module errors are wrapped transparently: display and source chain are the ones of the inner error
+#[derive(Debug, Error)]
+pub enum Error {
+    #[error(transparent)]
+    Yahoo(#[from] YahooErr),
+    #[error(transparent)]
+    AlphaVantage(#[from] AlphaVantageErr),
+    #[cfg(feature = "arrow")]
+    #[error(transparent)]
+    Arrow(#[from] ArrowError),
+    #[error(transparent)]
+    Cache(#[from] CacheError),
+    #[error(transparent)]
+    Csv(#[from] CsvError),
+    #[error(transparent)]
+    Fx(#[from] FxError),
+    #[error(transparent)]
+    Panel(#[from] PanelError),
+    #[cfg(feature = "polars")]
+    #[error(transparent)]
+    DataFrame(#[from] DataFrameError),
+    #[error(transparent)]
+    Resample(#[from] ResampleError),
+    #[error(transparent)]
+    DateTime(#[from] DateTimeError),
+    #[error(transparent)]
+    Db(#[from] DbError),
+    #[error(transparent)]
+    DbQuery(#[from] DbQueryError),
+    #[error(transparent)]
+    Portfolio(#[from] PortfolioError),
+    #[error(transparent)]
+    Returns(#[from] ReturnsError),
+    #[error(transparent)]
+    Matrix(#[from] MatrixError),
+    #[error(transparent)]
+    Sequence(#[from] SequenceErr),
+    #[error(transparent)]
+    RootFinding(#[from] RootFindingError),
+    #[error(transparent)]
+    EmptyInput(#[from] EmptyInput),
+    #[error(transparent)]
+    MinMax(#[from] MinMaxErr),
+    #[error(transparent)]
+    MultiInput(#[from] MultiInputErr),
+    #[error(transparent)]
+    Quantile(#[from] QuantileErr),
+    #[error(transparent)]
+    ShapeMismatch(#[from] ShapeMismatch),
+}
+
+pub type Result<T> = std::result::Result<T, Error>;
@@ -1,5 +1,8 @@
 pub mod commons;
 pub mod data;
 pub mod database;
+pub mod error;
 pub mod portfolio;
 pub mod quantitative;
+
+pub use error::{Error, Result};
@@ -1,18 +1,43 @@
 use ndarray::Array1;
+use rust_decimal::Decimal;
+use std::{
+    collections::{BTreeMap, HashMap},
+    time::Duration,
+};
 use thiserror::Error;
+use time::Date;
 
 use super::position::Position;
-use crate::{database::queries::VWPortfolio, quantitative::returns::ReturnsError};
+use crate::{
+    data::fundamentals::QuoteSummary,
+    data::fx::{FxError, FxRates},
+    data::provider::MarketDataProvider,
+    data::stream::{poll_quotes, poll_timer, QuoteTick},
+    database::queries::{Transaction, TransactionKind, VWPortfolio},
+    quantitative::returns::ReturnsError,
+};
 
 #[derive(Debug, Error)]
 pub enum PortfolioError {
     #[error("Failed to calculate returns: {0}")]
-    ReturnsError(String),
+    ReturnsError(#[from] ReturnsError),
+    #[error("Invalid transactions ledger: {0}")]
+    InvalidLedger(String),
 }
 
+// running state of a ticker while replaying the ledger
+struct Holding {
+    quantity: Decimal,
+    cost: Decimal,
+    currency: String,
+    open_date: String,
+}
+
+// struct to model a portfolio, amounts are f64 by default
+// `Portfolio<Decimal>` keeps database decimals exact end to end, see `Portfolio::<Decimal>::to_f64`
 #[derive(Debug)]
-pub struct Portfolio {
-    pub positions: Array1<Position>,
+pub struct Portfolio<T = f64> {
+    pub positions: Array1<Position<T>>,
 }
 
 // default method implementation
@@ -30,26 +55,86 @@ impl Portfolio {
         }
     }
 
-    // create portfolio instance from database (currently personal MySql db)
+    // create portfolio instance from database (`vw_portfolio` view of any supported backend)
+    // values that can not be decoded fail in `portfolio_table` with `DbQueryError::InvalidPosition`
     pub fn from_database(data: Vec<VWPortfolio>) -> Self {
-        let p = data
-            .into_iter()
-            .map(|e| Position {
-                symbol: e.ticker,
-                quantity: i32::try_from(e.quantity).unwrap(),
-                currency: e.currency,
-                buy_date: e.buy_date.to_string(),
-                buy_price: f64::try_from(e.buy_price).unwrap(),
-                buy_value: f64::try_from(e.buy_value).unwrap(),
-                market_price: f64::try_from(e.market_price).unwrap(),
-                market_value: f64::try_from(e.market_value).unwrap(),
-                pl: f64::try_from(e.pl).unwrap(),
-                pl_pct: f64::try_from(e.pl_pct).unwrap(),
-                weight: 0.0,
-            })
-            .collect::<Vec<Position>>();
-        Portfolio {
-            positions: p.into(),
+        Portfolio::from_database_decimal(data).to_f64()
+    }
+
+    // create portfolio instance by replaying a transactions ledger (see `from_transactions_decimal`)
+    pub fn from_transactions(transactions: &[Transaction]) -> Result<Self, PortfolioError> {
+        Ok(Portfolio::from_transactions_decimal(transactions)?.to_f64())
+    }
+
+    // update market price, market value and profit and loss of every position
+    pub async fn update_mkt_prices<P: MarketDataProvider>(
+        &mut self,
+        provider: &P,
+    ) -> Result<(), P::Error> {
+        for p in &mut self.positions {
+            p.update_mkt_price(provider).await?;
+            p.equity();
+            p.pl();
+            p.pl_pct();
+        }
+        self.weights();
+        Ok(())
+    }
+
+    // apply a streamed price to every position on the same symbol, returns whether any position changed
+    pub fn apply_tick(&mut self, tick: &QuoteTick) -> bool {
+        let mut updated = false;
+        for p in self
+            .positions
+            .iter_mut()
+            .filter(|p| p.symbol == tick.symbol)
+        {
+            p.market_price = tick.price;
+            p.equity();
+            p.pl();
+            p.pl_pct();
+            updated = true;
+        }
+        if updated {
+            self.weights();
+        }
+        updated
+    }
+
+    // keep positions updated by polling the provider latest quotes every `interval`, at least
+    // `stream::MIN_POLL_INTERVAL`
+    // `on_update` is called after every poll cycle, even when no price moved or polls failed,
+    // and the subscription stops as soon as it returns false
+    // transient provider errors are skipped (the next poll retries), other errors end the subscription
+    pub async fn subscribe<P, F>(
+        &mut self,
+        provider: &P,
+        interval: Duration,
+        mut on_update: F,
+    ) -> Result<(), P::Error>
+    where
+        P: MarketDataProvider,
+        F: FnMut(&Portfolio) -> bool,
+    {
+        let mut tickers: Vec<String> = self.positions.iter().map(|p| p.symbol.clone()).collect();
+        tickers.sort();
+        tickers.dedup();
+
+        let mut timer = poll_timer(interval);
+        loop {
+            timer.tick().await;
+            for update in poll_quotes(provider, &tickers).await {
+                match update {
+                    Ok(tick) => {
+                        self.apply_tick(&tick);
+                    }
+                    Err((_, e)) if provider.is_transient(&e) => continue,
+                    Err((_, e)) => return Err(e),
+                }
+            }
+            if !on_update(self) {
+                return Ok(());
+            }
         }
     }
 
@@ -63,6 +148,38 @@ impl Portfolio {
         self.positions.iter().map(|p| p.market_value).sum::<f64>()
     }
 
+    // calculate total market value in `base` currency at `date` rates
+    pub fn total_mkt_val_in(
+        &self,
+        rates: &FxRates,
+        base: &str,
+        date: Date,
+    ) -> Result<f64, FxError> {
+        self.positions
+            .iter()
+            .map(|p| rates.convert(p.market_value, &p.currency, base, date))
+            .sum()
+    }
+
+    // convert every position into `base` currency (see `Position::to_currency`) and recompute weights
+    pub fn to_currency(
+        &self,
+        rates: &FxRates,
+        base: &str,
+        date: Date,
+    ) -> Result<Portfolio, FxError> {
+        let mut portfolio = Portfolio {
+            positions: self
+                .positions
+                .iter()
+                .map(|p| p.to_currency(rates, base, date))
+                .collect::<Result<Vec<Position>, FxError>>()?
+                .into(),
+        };
+        portfolio.weights();
+        Ok(portfolio)
+    }
+
     // calculate overall profit and loss
     pub fn total_pl(&self) -> f64 {
         self.positions.iter().map(|p| p.pl).sum::<f64>()
@@ -86,6 +203,20 @@ impl Portfolio {
         }
     }
 
+    // aggregate position weights by sector, positions without a known sector are grouped under "Unknown"
+    pub fn sector_exposure(&self, summaries: &[QuoteSummary]) -> HashMap<String, f64> {
+        let mut exposure: HashMap<String, f64> = HashMap::new();
+        for p in &self.positions {
+            let sector = summaries
+                .iter()
+                .find(|s| s.symbol == p.symbol)
+                .and_then(|s| s.sector.clone())
+                .unwrap_or_else(|| "Unknown".to_string());
+            *exposure.entry(sector).or_default() += p.weight;
+        }
+        exposure
+    }
+
     // calculate portfolio return
     pub fn portfolio_return(&self) -> f64 {
         self.positions
@@ -103,3 +234,182 @@ impl Portfolio {
         todo!()
     }
 }
+
+// exact decimal portfolio, built from the database without any lossy conversion
+impl Portfolio<Decimal> {
+    // create decimal portfolio instance from database (`vw_portfolio` view)
+    pub fn from_database_decimal(data: Vec<VWPortfolio>) -> Self {
+        let p = data
+            .into_iter()
+            .map(|e| Position {
+                symbol: e.ticker,
+                quantity: e.quantity,
+                currency: e.currency,
+                buy_date: e.buy_date.to_string(),
+                buy_price: e.buy_price,
+                buy_value: e.buy_value,
+                market_price: e.market_price,
+                market_value: e.market_value,
+                pl: e.pl,
+                pl_pct: e.pl_pct,
+                weight: Decimal::ZERO,
+            })
+            .collect::<Vec<Position<Decimal>>>();
+        Portfolio {
+            positions: p.into(),
+        }
+    }
+
+    // create decimal portfolio instance by replaying a transactions ledger in trade date order
+    // cost basis follows the average cost method: buys add quantity and cost (fees included), sells
+    // remove quantity at the average cost, splits multiply quantity by the ratio keeping the cost
+    // sell fees only reduce the sale proceeds, which are not tracked, so they are ignored here
+    // cash events (dividend, fee, deposit, withdrawal) do not change positions
+    // trades with a non-positive quantity (or split ratio) make the ledger invalid
+    // positions are valued at cost until market prices are updated
+    pub fn from_transactions_decimal(transactions: &[Transaction]) -> Result<Self, PortfolioError> {
+        let mut ledger: Vec<&Transaction> = transactions.iter().collect();
+        ledger.sort_by_key(|t| (t.trade_date, t.id));
+
+        let mut holdings: BTreeMap<String, Holding> = BTreeMap::new();
+        for t in ledger {
+            if !matches!(
+                t.kind,
+                TransactionKind::Buy | TransactionKind::Sell | TransactionKind::Split
+            ) {
+                continue;
+            }
+            let ticker = t.ticker.clone().ok_or_else(|| {
+                PortfolioError::InvalidLedger(format!(
+                    "{} on {} has no ticker",
+                    t.kind, t.trade_date
+                ))
+            })?;
+            if t.quantity <= Decimal::ZERO {
+                return Err(PortfolioError::InvalidLedger(format!(
+                    "{} of {} on {} has non-positive {} {}",
+                    t.kind,
+                    ticker,
+                    t.trade_date,
+                    if t.kind == TransactionKind::Split {
+                        "ratio"
+                    } else {
+                        "quantity"
+                    },
+                    t.quantity
+                )));
+            }
+            let h = holdings.entry(ticker.clone()).or_insert_with(|| Holding {
+                quantity: Decimal::ZERO,
+                cost: Decimal::ZERO,
+                currency: t.currency.clone(),
+                open_date: t.trade_date.to_string(),
+            });
+            let overflow = || {
+                PortfolioError::InvalidLedger(format!(
+                    "transaction {} ({} of {} on {}) overflows the decimal range",
+                    t.id.map_or_else(|| "without id".to_string(), |id| id.to_string()),
+                    t.kind,
+                    ticker,
+                    t.trade_date
+                ))
+            };
+            match t.kind {
+                TransactionKind::Buy => {
+                    if h.quantity.is_zero() {
+                        h.open_date = t.trade_date.to_string();
+                    }
+                    h.quantity = h.quantity.checked_add(t.quantity).ok_or_else(overflow)?;
+                    h.cost = t
+                        .quantity
+                        .checked_mul(t.price)
+                        .and_then(|v| v.checked_add(t.fees))
+                        .and_then(|v| h.cost.checked_add(v))
+                        .ok_or_else(overflow)?;
+                }
+                TransactionKind::Sell => {
+                    if t.quantity > h.quantity {
+                        return Err(PortfolioError::InvalidLedger(format!(
+                            "{} sold {} shares on {} but only {} are held",
+                            ticker, t.quantity, t.trade_date, h.quantity
+                        )));
+                    }
+                    h.cost = h
+                        .cost
+                        .checked_mul(t.quantity)
+                        .and_then(|v| v.checked_div(h.quantity))
+                        .and_then(|v| h.cost.checked_sub(v))
+                        .ok_or_else(overflow)?;
+                    h.quantity -= t.quantity;
+                }
+                _ => h.quantity = h.quantity.checked_mul(t.quantity).ok_or_else(overflow)?,
+            }
+        }
+
+        let positions = holdings
+            .into_iter()
+            .filter(|(_, h)| !h.quantity.is_zero())
+            .map(|(ticker, h)| {
+                let buy_price = h.cost.checked_div(h.quantity).ok_or_else(|| {
+                    PortfolioError::InvalidLedger(format!(
+                        "average buy price of {} overflows the decimal range",
+                        ticker
+                    ))
+                })?;
+                Ok(Position {
+                    symbol: ticker,
+                    quantity: h.quantity,
+                    currency: h.currency,
+                    buy_date: h.open_date,
+                    buy_price,
+                    buy_value: h.cost,
+                    market_price: buy_price,
+                    market_value: h.cost,
+                    pl: Decimal::ZERO,
+                    pl_pct: Decimal::ZERO,
+                    weight: Decimal::ZERO,
+                })
+            })
+            .collect::<Result<Vec<Position<Decimal>>, PortfolioError>>()?;
+
+        let mut portfolio = Portfolio {
+            positions: positions.into(),
+        };
+        portfolio.weights();
+        Ok(portfolio)
+    }
+
+    // convert into a f64 portfolio, see `Position::<Decimal>::to_f64`
+    pub fn to_f64(&self) -> Portfolio {
+        Portfolio {
+            positions: self.positions.map(Position::<Decimal>::to_f64),
+        }
+    }
+
+    // calculate total investments across all positions
+    pub fn total_investments(&self) -> Decimal {
+        self.positions.iter().map(|p| p.buy_value).sum()
+    }
+
+    // calculate total market value
+    pub fn total_mkt_val(&self) -> Decimal {
+        self.positions.iter().map(|p| p.market_value).sum()
+    }
+
+    // calculate overall profit and loss
+    pub fn total_pl(&self) -> Decimal {
+        self.positions.iter().map(|p| p.pl).sum()
+    }
+
+    // calculate positions weights, zero when the portfolio has no market value
+    pub fn weights(&mut self) {
+        let total = self.total_mkt_val();
+        for p in &mut self.positions {
+            p.weight = if total.is_zero() {
+                Decimal::ZERO
+            } else {
+                p.market_value / total
+            };
+        }
+    }
+}
@@ -1,27 +1,33 @@
 //! position
 
-use crate::data::yahoo::{Yahoo, YahooErr};
+use crate::commons::date::{parse_date, DateType};
+use crate::data::fx::{FxError, FxRates};
+use crate::data::provider::MarketDataProvider;
+use rust_decimal::{prelude::ToPrimitive, Decimal};
+use time::Date;
 
+// struct to model a portfolio position, quantities and amounts are f64 by default
+// `Position<Decimal>` keeps database decimals exact, see `Position::<Decimal>::to_f64`
 #[derive(Debug)]
-pub struct Position {
+pub struct Position<T = f64> {
     pub symbol: String,
-    pub quantity: i32,
+    pub quantity: T,
     pub currency: String,
     pub buy_date: String,
-    pub buy_price: f64,
-    pub buy_value: f64,
-    pub market_price: f64,
-    pub market_value: f64,
-    pub pl: f64,
-    pub pl_pct: f64,
-    pub weight: f64,
+    pub buy_price: T,
+    pub buy_value: T,
+    pub market_price: T,
+    pub market_value: T,
+    pub pl: T,
+    pub pl_pct: T,
+    pub weight: T,
 }
 
 impl Position {
     // create new positions instance
     pub fn new(
         symbol: &str,
-        quantity: i32,
+        quantity: f64,
         currency: &str,
         buy_date: &str,
         buy_price: f64,
@@ -42,30 +48,53 @@ impl Position {
         }
     }
 
-    // update position market price
-    pub async fn update_mkt_price(&mut self) -> Result<(), YahooErr> {
-        let conn = Yahoo::provider()?;
-        match conn.get_latest_quote(&self.symbol).await {
-            Ok(p) => {
-                self.market_price = p;
-                println!("Seuccessfully updated market price for {}", self.symbol);
-                Ok(())
-            }
-            Err(err) => {
-                eprintln!("Error updating market price for {}: {}", self.symbol, err);
-                Err(YahooErr::FetchFailed(err.to_string()))
-            }
-        }
+    // update position market price using the given market data provider
+    pub async fn update_mkt_price<P: MarketDataProvider>(
+        &mut self,
+        provider: &P,
+    ) -> Result<(), P::Error> {
+        self.market_price = provider.get_latest_quote(&self.symbol).await?;
+        Ok(())
+    }
+
+    // convert position into `base` currency, cost basis at the buy date rate and market values at `date` rate
+    // profit and loss is recomputed so it includes the currency effect
+    pub fn to_currency(
+        &self,
+        rates: &FxRates,
+        base: &str,
+        date: Date,
+    ) -> Result<Position, FxError> {
+        let buy_date = parse_date(&self.buy_date, DateType::Start)?.date();
+        let buy_rate = rates.rate(&self.currency, base, buy_date)?;
+        let mkt_rate = rates.rate(&self.currency, base, date)?;
+
+        let mut position = Position {
+            symbol: self.symbol.clone(),
+            quantity: self.quantity,
+            currency: base.to_uppercase(),
+            buy_date: self.buy_date.clone(),
+            buy_price: self.buy_price * buy_rate,
+            buy_value: self.buy_value * buy_rate,
+            market_price: self.market_price * mkt_rate,
+            market_value: self.market_value * mkt_rate,
+            pl: 0.0,
+            pl_pct: 0.0,
+            weight: self.weight,
+        };
+        position.pl();
+        position.pl_pct();
+        Ok(position)
     }
 
     // calculate position buy value (total invested in the position)
     pub fn buy_value(&mut self) {
-        self.buy_value = self.buy_price * self.quantity as f64
+        self.buy_value = self.buy_price * self.quantity
     }
 
     // calculate position equity (current market value of the position)
     pub fn equity(&mut self) {
-        self.market_value = self.market_price * self.quantity as f64
+        self.market_value = self.market_price * self.quantity
     }
 
     // calculate profit and loss
@@ -78,3 +107,49 @@ impl Position {
         self.pl_pct = self.market_value / self.buy_value - 1.
     }
 }
+
+// exact decimal arithmetic, e.g. for accounting on values read from the database
+impl Position<Decimal> {
+    // calculate position buy value (total invested in the position)
+    pub fn buy_value(&mut self) {
+        self.buy_value = self.buy_price * self.quantity
+    }
+
+    // calculate position equity (current market value of the position)
+    pub fn equity(&mut self) {
+        self.market_value = self.market_price * self.quantity
+    }
+
+    // calculate profit and loss
+    pub fn pl(&mut self) {
+        self.pl = self.market_value - self.buy_value
+    }
+
+    // calculate percentage profit and loss, zero when nothing was invested
+    pub fn pl_pct(&mut self) {
+        self.pl_pct = if self.buy_value.is_zero() {
+            Decimal::ZERO
+        } else {
+            self.market_value / self.buy_value - Decimal::ONE
+        }
+    }
+
+    // convert into a f64 position, rounding every amount to the nearest f64
+    pub fn to_f64(&self) -> Position {
+        // rust_decimal maps every decimal (at most ~7.9e28) to its nearest f64, never none
+        let f = |value: Decimal| value.to_f64().expect("Decimal always converts to f64");
+        Position {
+            symbol: self.symbol.clone(),
+            quantity: f(self.quantity),
+            currency: self.currency.clone(),
+            buy_date: self.buy_date.clone(),
+            buy_price: f(self.buy_price),
+            buy_value: f(self.buy_value),
+            market_price: f(self.market_price),
+            market_value: f(self.market_value),
+            pl: f(self.pl),
+            pl_pct: f(self.pl_pct),
+            weight: f(self.weight),
+        }
+    }
+}
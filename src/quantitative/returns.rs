@@ -2,8 +2,8 @@ use std::collections::HashMap;
 
 use ndarray::Array1;
 use thiserror::Error;
+use time::{Date, OffsetDateTime};
 
-use crate::commons::parser::round_to_four;
 use crate::data::yahoo::{MultiQuoteItem, QuoteItem};
 
 #[derive(Debug, Error)]
@@ -22,12 +22,12 @@ pub enum ReturnType {
     Logarithmic,
 }
 
-type MultiquoteReturns = (String, HashMap<String, f64>);
+type QuoteReturns = (OffsetDateTime, f64);
+type MultiquoteReturns = (Date, HashMap<String, f64>);
 
 pub trait Returns {
-    fn returns(&self, r_type: ReturnType) -> Result<Array1<(String, f64)>, ReturnsError>;
-    fn cumulative_returns(&self, r_type: ReturnType)
-        -> Result<Array1<(String, f64)>, ReturnsError>;
+    fn returns(&self, r_type: ReturnType) -> Result<Array1<QuoteReturns>, ReturnsError>;
+    fn cumulative_returns(&self, r_type: ReturnType) -> Result<Array1<QuoteReturns>, ReturnsError>;
     fn returns_multiquote(
         &self,
         r_type: ReturnType,
@@ -40,12 +40,12 @@ pub trait Returns {
 
 impl Returns for Array1<QuoteItem> {
     // compute returns of data contained into an array composed by QuoteItem. Returns an array of tuples (datetime, return value)
-    fn returns(&self, r_type: ReturnType) -> Result<Array1<(String, f64)>, ReturnsError> {
+    fn returns(&self, r_type: ReturnType) -> Result<Array1<QuoteReturns>, ReturnsError> {
         if self.len() < 2 {
             return Err(ReturnsError::LengthError(self.len()));
         }
 
-        let mut r = Array1::from_elem(self.len() - 1, (String::new(), 0.0));
+        let mut r = Vec::with_capacity(self.len() - 1);
         for i in 1..self.len() {
             if self[i - 1].adjclose == 0.0 {
                 return Err(ReturnsError::LengthError(self.len()));
@@ -56,31 +56,26 @@ impl Returns for Array1<QuoteItem> {
                 ReturnType::Logarithmic => (self[i].adjclose / self[i - 1].adjclose).ln(),
                 ReturnType::Absolute => self[i].adjclose / self[i - 1].adjclose,
             };
-            r[i - 1] = (self[i].datetime.clone(), round_to_four(r_val));
+            r.push((self[i].datetime, r_val));
         }
-        Ok(r)
+        Ok(Array1::from_vec(r))
     }
 
-    fn cumulative_returns(
-        &self,
-        r_type: ReturnType,
-    ) -> Result<Array1<(String, f64)>, ReturnsError> {
+    fn cumulative_returns(&self, r_type: ReturnType) -> Result<Array1<QuoteReturns>, ReturnsError> {
         let ret = self.returns(r_type)?;
-        let mut c_ret = Array1::from_elem(self.len() - 1, (String::new(), 0.0));
 
         let mut c_val = 0.0;
-        for i in 0..ret.len() {
-            c_val = (1.0 + c_val) * (1.0 + ret[i].1) - 1.0;
-            c_ret[i] = (ret[i].0.clone(), round_to_four(c_val))
-        }
-        Ok(c_ret)
+        Ok(ret.mapv(|(d, r)| {
+            c_val = (1.0 + c_val) * (1.0 + r) - 1.0;
+            (d, c_val)
+        }))
     }
 
     #[allow(unused_variables)]
     fn returns_multiquote(
         &self,
         r_type: ReturnType,
-    ) -> Result<Array1<(String, HashMap<String, f64>)>, ReturnsError> {
+    ) -> Result<Array1<MultiquoteReturns>, ReturnsError> {
         Err(ReturnsError::NotImplementedFor(
             "Array1<QuoteItem>".to_string(),
         ))
@@ -90,7 +85,7 @@ impl Returns for Array1<QuoteItem> {
     fn cumulative_returns_multiquote(
         &self,
         r_type: ReturnType,
-    ) -> Result<Array1<(String, HashMap<String, f64>)>, ReturnsError> {
+    ) -> Result<Array1<MultiquoteReturns>, ReturnsError> {
         Err(ReturnsError::NotImplementedFor(
             "Array1<QuoteItem>".to_string(),
         ))
@@ -99,17 +94,14 @@ impl Returns for Array1<QuoteItem> {
 
 impl Returns for Array1<MultiQuoteItem> {
     #[allow(unused_variables)]
-    fn returns(&self, r_type: ReturnType) -> Result<Array1<(String, f64)>, ReturnsError> {
+    fn returns(&self, r_type: ReturnType) -> Result<Array1<QuoteReturns>, ReturnsError> {
         Err(ReturnsError::NotImplementedFor(
             "Array1<MultiQuoteItem>".to_string(),
         ))
     }
 
     #[allow(unused_variables)]
-    fn cumulative_returns(
-        &self,
-        r_type: ReturnType,
-    ) -> Result<Array1<(String, f64)>, ReturnsError> {
+    fn cumulative_returns(&self, r_type: ReturnType) -> Result<Array1<QuoteReturns>, ReturnsError> {
         Err(ReturnsError::NotImplementedFor(
             "Array1<MultiQuoteItem>".to_string(),
         ))
@@ -118,12 +110,12 @@ impl Returns for Array1<MultiQuoteItem> {
     fn returns_multiquote(
         &self,
         r_type: ReturnType,
-    ) -> Result<Array1<(String, HashMap<String, f64>)>, ReturnsError> {
+    ) -> Result<Array1<MultiquoteReturns>, ReturnsError> {
         if self.len() < 2 {
             return Err(ReturnsError::LengthError(self.len()));
         }
 
-        let mut r = Array1::from_elem(self.len() - 1, (String::new(), HashMap::new()));
+        let mut r = Vec::with_capacity(self.len() - 1);
         for i in 1..self.len() {
             let prev = &self[i - 1];
             let curr = &self[i];
@@ -136,33 +128,33 @@ impl Returns for Array1<MultiQuoteItem> {
                         ReturnType::Logarithmic => (curr_p / prev_p).ln(),
                         ReturnType::Absolute => curr_p / prev_p,
                     };
-                    ret_map.insert(t.clone(), round_to_four(r_val));
+                    ret_map.insert(t.clone(), r_val);
                 }
             }
-            r[i - 1] = (curr.date.clone(), ret_map);
+            r.push((curr.date, ret_map));
         }
-        Ok(r)
+        Ok(Array1::from_vec(r))
     }
 
     fn cumulative_returns_multiquote(
         &self,
         r_type: ReturnType,
-    ) -> Result<Array1<(String, HashMap<String, f64>)>, ReturnsError> {
+    ) -> Result<Array1<MultiquoteReturns>, ReturnsError> {
         let ret = self.returns_multiquote(r_type)?;
 
-        let mut cum_arr = Array1::from_elem(ret.len(), (String::new(), HashMap::new()));
+        let mut cum_arr = Vec::with_capacity(ret.len());
         let mut cum_map: HashMap<String, f64> = HashMap::new();
 
-        for (i, (d, r_map)) in ret.iter().enumerate() {
+        for (d, r_map) in ret.iter() {
             let mut curr = HashMap::new();
             for (t, r_val) in r_map.iter() {
                 let cum_entry = cum_map.entry(t.clone()).or_insert(0.0);
 
                 *cum_entry = (1.0 + *cum_entry) * (1.0 + r_val) - 1.0;
-                curr.insert(t.clone(), round_to_four(*cum_entry));
+                curr.insert(t.clone(), *cum_entry);
             }
-            cum_arr[i] = (d.clone(), curr);
+            cum_arr.push((*d, curr));
         }
-        Ok(cum_arr)
+        Ok(Array1::from_vec(cum_arr))
     }
 }
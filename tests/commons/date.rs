@@ -1,8 +1,12 @@
+use ndarray::Array1;
 use rs_quant::commons::date::{
-    datetime_to_date, parse_date, timestamp_to_datetime, timestamp_to_localdt, DateType,
+    date_to_epoch_days, datetime_to_date, datetime_to_micros, epoch_days_to_date,
+    local_to_exchange_dt, nanos_to_exchange_dt, parse_date, single_timezone, timestamp_to_datetime,
+    timestamp_to_exchange_dt, DateTimeError, DateType,
 };
-use time::macros::{datetime, format_description};
-use time::{OffsetDateTime, UtcOffset};
+use rs_quant::data::yahoo::QuoteItem;
+use std::error::Error;
+use time::macros::{date, datetime};
 
 #[test]
 fn test_parse_date() {
@@ -11,6 +15,13 @@ fn test_parse_date() {
 
     let end = parse_date("2021-06-17", DateType::End).unwrap();
     assert_eq!(end, datetime!(2021-06-17 23:59:59.999999 UTC));
+
+    let r = parse_date("2021-13-17", DateType::Start);
+    assert!(
+        matches!(&r, Err(e @ DateTimeError::InvalidDateFormat(..)) if e.source().is_some_and(|s| s.is::<time::error::Parse>())),
+        "error result: {:?}",
+        r
+    );
 }
 
 #[test]
@@ -35,18 +46,35 @@ fn invalid_timestamp() {
 }
 
 #[test]
-fn test_timestamp_to_localdt() {
-    let timestamp = OffsetDateTime::now_utc().unix_timestamp() as u64;
-    let r = timestamp_to_localdt(timestamp).unwrap();
+fn test_timestamp_to_exchange_dt() {
+    // winter time: milan is utc+1, new york is utc-5
+    let timestamp = 1732440896;
+    let r = timestamp_to_exchange_dt(timestamp, "Europe/Rome").unwrap();
+    assert_eq!(r, datetime!(2024-11-24 10:34:56 +1));
+    assert_eq!(r.offset().whole_hours(), 1);
+    let r = timestamp_to_exchange_dt(timestamp, "America/New_York").unwrap();
+    assert_eq!(r.offset().whole_hours(), -5);
 
-    let format = format_description!("[year]-[month]-[day] [hour]:[minute]:[second]");
-    let local_offset = UtcOffset::from_hms(1, 0, 0).unwrap();
-    let expected_datetime = OffsetDateTime::now_utc()
-        .to_offset(local_offset)
-        .format(&format)
-        .unwrap();
+    // summer time: milan is utc+2, new york is utc-4
+    let timestamp = 1720000000;
+    let r = timestamp_to_exchange_dt(timestamp, "Europe/Rome").unwrap();
+    assert_eq!(r, datetime!(2024-07-03 11:46:40 +2));
+    let r = timestamp_to_exchange_dt(timestamp, "America/New_York").unwrap();
+    assert_eq!(r.offset().whole_hours(), -4);
+    assert_eq!(r.date(), datetime!(2024-07-03 0:00 UTC).date());
 
-    assert_eq!(r, expected_datetime);
+    let r = timestamp_to_exchange_dt(timestamp, "Mars/Olympus_Mons");
+    assert!(matches!(r, Err(DateTimeError::UnknownTimezone(_))));
+}
+
+#[test]
+fn test_local_to_exchange_dt() {
+    let r = local_to_exchange_dt(datetime!(2024-02-01 09:30:00), "America/New_York").unwrap();
+    assert_eq!(r, datetime!(2024-02-01 09:30:00 -5));
+
+    // local time skipped by dst change does not exist
+    let r = local_to_exchange_dt(datetime!(2024-03-31 02:30:00), "Europe/Rome");
+    assert!(matches!(r, Err(DateTimeError::NonexistentLocalTime(_, _))));
 }
 
 #[test]
@@ -55,3 +83,48 @@ fn test_datetime_to_date() {
     let date = datetime_to_date(datetime).unwrap();
     assert_eq!(date, "2024-11-30");
 }
+
+#[test]
+fn test_epoch_conversions() {
+    assert_eq!(date_to_epoch_days(date!(1970 - 01 - 01)), 0);
+    assert_eq!(date_to_epoch_days(date!(2024 - 02 - 01)), 19754);
+    assert_eq!(epoch_days_to_date(19754).unwrap(), date!(2024 - 02 - 01));
+    assert!(matches!(
+        epoch_days_to_date(i32::MAX - 2_440_588),
+        Err(DateTimeError::InvalidEpochDays(..))
+    ));
+
+    let dt = datetime!(2024-02-01 9:00:00.123456 +1);
+    let micros = datetime_to_micros(dt);
+    assert_eq!(micros, 1_706_774_400_123_456);
+    let r = nanos_to_exchange_dt(micros as i128 * 1_000, "Europe/Rome").unwrap();
+    assert_eq!(r, dt);
+    assert_eq!(r.offset().whole_hours(), 1);
+    assert!(matches!(
+        nanos_to_exchange_dt(0, "Mars/Olympus"),
+        Err(DateTimeError::UnknownTimezone(_))
+    ));
+}
+
+#[test]
+fn test_single_timezone() {
+    let quote = |timezone: &str| QuoteItem {
+        datetime: datetime!(2024-02-01 9:00 +1),
+        timezone: timezone.to_string(),
+        open: 1.0,
+        high: 1.0,
+        low: 1.0,
+        close: 1.0,
+        adjclose: 1.0,
+        volume: 0,
+    };
+    assert_eq!(single_timezone(&Array1::from_vec(vec![])).unwrap(), "UTC");
+    let quotes = Array1::from_vec(vec![quote("Europe/Rome"), quote("Europe/Rome")]);
+    assert_eq!(single_timezone(&quotes).unwrap(), "Europe/Rome");
+
+    let quotes = Array1::from_vec(vec![quote("Europe/Rome"), quote("America/New_York")]);
+    assert!(matches!(
+        single_timezone(&quotes),
+        Err(DateTimeError::MixedTimezones(a, b)) if a == "Europe/Rome" && b == "America/New_York"
+    ));
+}
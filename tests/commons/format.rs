@@ -0,0 +1,60 @@
+use std::collections::HashMap;
+
+use ndarray::array;
+use rs_quant::commons::format::Round;
+use rs_quant::commons::parser::round_to;
+use rs_quant::data::yahoo::{MultiQuoteItem, QuoteItem};
+use time::macros::{date, datetime};
+
+#[test]
+fn test_round_to() {
+    assert_eq!(round_to(1.23456789, 0), 1.0);
+    assert_eq!(round_to(1.23456789, 2), 1.23);
+    assert_eq!(round_to(1.23456789, 6), 1.234568);
+    assert_eq!(round_to(-0.00005, 4), -0.0001);
+}
+
+#[test]
+fn round_quotes() {
+    let q = array![QuoteItem {
+        datetime: datetime!(2024-02-01 0:00 UTC),
+        timezone: "UTC".to_string(),
+        open: 1.084_372,
+        high: 1.089_915,
+        low: 1.081_004,
+        close: 1.085_551,
+        adjclose: 1.085_551,
+        volume: 12345,
+    }];
+    let r = q.rounded(4);
+    assert_eq!(r[0].open, 1.0844);
+    assert_eq!(r[0].high, 1.0899);
+    assert_eq!(r[0].low, 1.081);
+    assert_eq!(r[0].adjclose, 1.0856);
+    assert_eq!(r[0].volume, 12345);
+    assert_eq!(r[0].datetime, q[0].datetime);
+
+    // original data keeps full precision
+    assert_eq!(q[0].open, 1.084_372);
+}
+
+#[test]
+fn round_returns() {
+    let r = array![
+        (datetime!(2024-02-02 0:00 UTC), 0.142_857_142_857),
+        (datetime!(2024-02-05 0:00 UTC), -0.183_333_333_333)
+    ];
+    assert_eq!(
+        r.rounded(4),
+        array![
+            (datetime!(2024-02-02 0:00 UTC), 0.1429),
+            (datetime!(2024-02-05 0:00 UTC), -0.1833)
+        ]
+    );
+
+    let m = array![MultiQuoteItem {
+        date: date!(2024 - 02 - 01),
+        prices: HashMap::from([("AAPL".to_string(), 150.123_456)]),
+    }];
+    assert_eq!(m.rounded(2)[0].prices["AAPL"], 150.12);
+}
@@ -1 +1,2 @@
 pub mod date;
+pub mod format;
@@ -0,0 +1,75 @@
+use std::collections::HashMap;
+
+use ndarray::Array1;
+use rs_quant::data::align::{Align, AlignPolicy};
+use rs_quant::data::yahoo::MultiQuoteItem;
+use time::macros::date;
+use time::Date;
+
+fn row(date: Date, prices: &[(&str, f64)]) -> MultiQuoteItem {
+    MultiQuoteItem {
+        date,
+        prices: prices.iter().map(|(t, p)| (t.to_string(), *p)).collect(),
+    }
+}
+
+// AAPL trades on every date, SAP misses the first and third one and has a NaN on the last one
+fn panel() -> Array1<MultiQuoteItem> {
+    Array1::from_vec(vec![
+        row(date!(2024 - 03 - 28), &[("AAPL", 100.0)]),
+        row(date!(2024 - 04 - 02), &[("AAPL", 101.0), ("SAP", 50.0)]),
+        row(date!(2024 - 04 - 03), &[("AAPL", 102.0)]),
+        row(date!(2024 - 04 - 04), &[("AAPL", 103.0), ("SAP", f64::NAN)]),
+    ])
+}
+
+fn dates(quotes: &Array1<MultiQuoteItem>) -> Vec<Date> {
+    quotes.iter().map(|q| q.date).collect()
+}
+
+#[test]
+fn inner() {
+    let (aligned, report) = panel().align(AlignPolicy::Inner);
+    assert_eq!(
+        dates(&aligned),
+        vec![date!(2024 - 04 - 02), date!(2024 - 04 - 04)]
+    );
+    assert_eq!(report.dropped_rows, 2);
+    assert_eq!(report.missing, HashMap::from([("SAP".to_string(), 3)]));
+    assert_eq!(report.total_filled(), 0);
+}
+
+#[test]
+fn drop_missing() {
+    let (aligned, report) = panel().align(AlignPolicy::DropMissing);
+    assert_eq!(dates(&aligned), vec![date!(2024 - 04 - 02)]);
+    assert_eq!(report.dropped_rows, 3);
+}
+
+#[test]
+fn forward_fill() {
+    let (aligned, report) = panel().align(AlignPolicy::ForwardFill);
+    assert_eq!(
+        dates(&aligned),
+        vec![
+            date!(2024 - 04 - 02),
+            date!(2024 - 04 - 03),
+            date!(2024 - 04 - 04)
+        ]
+    );
+    assert_eq!(aligned[1].prices["SAP"], 50.0);
+    assert_eq!(aligned[2].prices["SAP"], 50.0);
+    assert_eq!(report.filled, HashMap::from([("SAP".to_string(), 2)]));
+    assert_eq!(report.dropped_rows, 1);
+}
+
+#[test]
+fn fill_value() {
+    let (aligned, report) = panel().align(AlignPolicy::Fill(0.0));
+    assert_eq!(aligned.len(), 4);
+    assert!(aligned.iter().all(|q| q.prices.len() == 2));
+    assert_eq!(aligned[0].prices["SAP"], 0.0);
+    assert_eq!(aligned[3].prices["SAP"], 0.0);
+    assert_eq!(report.filled, HashMap::from([("SAP".to_string(), 3)]));
+    assert_eq!(report.dropped_rows, 0);
+}
@@ -0,0 +1,251 @@
+use rs_quant::data::alphavantage::{AlphaVantage, AlphaVantageErr};
+use rs_quant::data::provider::MarketDataProvider;
+use rs_quant::data::yahoo::OptionType;
+use time::macros::datetime;
+use wiremock::matchers::{method, path, query_param};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+// start a mock server answering `function` queries with the given body
+async fn mock_server(function: &str, body: &str) -> MockServer {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/query"))
+        .and(query_param("function", function))
+        .and(query_param("apikey", "demo"))
+        .respond_with(ResponseTemplate::new(200).set_body_string(body))
+        .mount(&server)
+        .await;
+    server
+}
+
+fn provider(server: &MockServer) -> AlphaVantage {
+    AlphaVantage::new("demo").with_base_url(&server.uri())
+}
+
+#[tokio::test]
+async fn get_daily_quotes() {
+    let server = mock_server(
+        "TIME_SERIES_DAILY",
+        include_str!("../fixtures/alphavantage/daily.json"),
+    )
+    .await;
+    let quotes = provider(&server)
+        .get_quotes("IBM", None, None, None, None)
+        .await
+        .unwrap();
+
+    assert_eq!(quotes.len(), 3);
+    assert_eq!(quotes[0].datetime, datetime!(2024-02-01 0:00 -5));
+    assert_eq!(quotes[0].timezone, "US/Eastern");
+    assert_eq!(quotes[0].open, 183.63);
+    assert_eq!(quotes[2].close, 185.58);
+    assert_eq!(quotes[2].adjclose, 185.58);
+    assert_eq!(quotes[2].volume, 4612718);
+}
+
+#[tokio::test]
+async fn get_quotes_with_date_range() {
+    let server = mock_server(
+        "TIME_SERIES_DAILY",
+        include_str!("../fixtures/alphavantage/daily.json"),
+    )
+    .await;
+    let quotes = provider(&server)
+        .get_quotes(
+            "IBM",
+            Some("2024-02-02"),
+            Some("2024-02-05"),
+            None,
+            Some("1d"),
+        )
+        .await
+        .unwrap();
+    assert_eq!(quotes.len(), 2);
+    assert_eq!(quotes[0].close, 185.79);
+
+    // bars east of utc start before the utc midnight of their exchange local date
+    let server = mock_server(
+        "TIME_SERIES_DAILY",
+        &include_str!("../fixtures/alphavantage/daily.json").replace("US/Eastern", "Asia/Tokyo"),
+    )
+    .await;
+    let quotes = provider(&server)
+        .get_quotes(
+            "IBM",
+            Some("2024-02-02"),
+            Some("2024-02-02"),
+            None,
+            Some("1d"),
+        )
+        .await
+        .unwrap();
+    assert_eq!(quotes.len(), 1);
+    assert_eq!(quotes[0].datetime, datetime!(2024-02-02 0:00 +9));
+}
+
+#[tokio::test]
+async fn output_size() {
+    let server = MockServer::start().await;
+    for (size, expected) in [("compact", 1), ("full", 2)] {
+        Mock::given(method("GET"))
+            .and(path("/query"))
+            .and(query_param("function", "TIME_SERIES_DAILY"))
+            .and(query_param("outputsize", size))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_string(include_str!("../fixtures/alphavantage/daily.json")),
+            )
+            .expect(expected)
+            .mount(&server)
+            .await;
+    }
+
+    // only requests without range or period use the compact (latest 100 bars) output
+    let av = provider(&server);
+    av.get_quotes("IBM", None, None, None, None).await.unwrap();
+    av.get_quotes("IBM", None, None, Some("max"), None)
+        .await
+        .unwrap();
+    av.get_quotes("IBM", None, None, Some("5d"), None)
+        .await
+        .unwrap();
+    server.verify().await;
+}
+
+#[tokio::test]
+async fn get_intraday_quotes() {
+    let server = mock_server(
+        "TIME_SERIES_INTRADAY",
+        include_str!("../fixtures/alphavantage/intraday.json"),
+    )
+    .await;
+    let quotes = provider(&server)
+        .get_quotes("IBM", None, None, None, Some("60m"))
+        .await
+        .unwrap();
+    assert_eq!(quotes.len(), 2);
+    assert_eq!(quotes[0].datetime, datetime!(2024-02-05 10:00 -5));
+}
+
+#[tokio::test]
+async fn get_monthly_adjusted_quotes() {
+    let server = mock_server(
+        "TIME_SERIES_MONTHLY_ADJUSTED",
+        include_str!("../fixtures/alphavantage/monthly_adjusted.json"),
+    )
+    .await;
+    let quotes = provider(&server)
+        .get_quotes("IBM", None, None, None, Some("1mo"))
+        .await
+        .unwrap();
+    assert_eq!(quotes[1].close, 185.03);
+    assert_eq!(quotes[1].adjclose, 183.35);
+}
+
+#[tokio::test]
+async fn get_latest_quote() {
+    let server = mock_server(
+        "GLOBAL_QUOTE",
+        include_str!("../fixtures/alphavantage/global_quote.json"),
+    )
+    .await;
+    let price = provider(&server).get_latest_quote("IBM").await.unwrap();
+    assert_eq!(price, 185.58);
+}
+
+#[tokio::test]
+async fn api_errors() {
+    let server = mock_server(
+        "TIME_SERIES_DAILY",
+        r#"{"Error Message": "Invalid API call."}"#,
+    )
+    .await;
+    let r = provider(&server)
+        .get_quotes("INVALID", None, None, None, None)
+        .await;
+    assert!(
+        matches!(r, Err(AlphaVantageErr::ApiError(_))),
+        "error result: {:?}",
+        r
+    );
+
+    let server = mock_server(
+        "TIME_SERIES_DAILY",
+        r#"{"Information": "Our standard API rate limit is 25 requests per day."}"#,
+    )
+    .await;
+    let av = provider(&server);
+    let r = av.get_quotes("IBM", None, None, None, None).await;
+    assert!(
+        matches!(&r, Err(e) if av.is_transient(e)),
+        "error result: {:?}",
+        r
+    );
+
+    // premium endpoint and invalid key notices come as "Information" too but are not retried
+    let server = mock_server(
+        "TIME_SERIES_DAILY",
+        r#"{"Information": "Thank you for using Alpha Vantage! This is a premium endpoint."}"#,
+    )
+    .await;
+    let av = provider(&server);
+    let r = av.get_quotes("IBM", None, None, None, None).await;
+    assert!(
+        matches!(&r, Err(e @ AlphaVantageErr::ApiError(_)) if !av.is_transient(e)),
+        "error result: {:?}",
+        r
+    );
+
+    let r = av.get_quotes("IBM", None, None, None, Some("2h")).await;
+    assert!(matches!(r, Err(AlphaVantageErr::UnsupportedInterval(_))));
+
+    let r = av.get_options("IBM", OptionType::Call).await;
+    assert!(matches!(r, Err(AlphaVantageErr::NotSupported(_))));
+}
+
+#[tokio::test]
+async fn retries_only_transient_http_errors() {
+    let server = MockServer::start().await;
+    for (symbol, status) in [
+        ("BADKEY", 401),
+        ("MISSING", 404),
+        ("LIMIT", 429),
+        ("DOWN", 503),
+    ] {
+        Mock::given(method("GET"))
+            .and(path("/query"))
+            .and(query_param("symbol", symbol))
+            .respond_with(ResponseTemplate::new(status))
+            .mount(&server)
+            .await;
+    }
+    let av = provider(&server);
+    for (symbol, transient) in [
+        ("BADKEY", false),
+        ("MISSING", false),
+        ("LIMIT", true),
+        ("DOWN", true),
+    ] {
+        let r = av.get_quotes(symbol, None, None, None, None).await;
+        assert!(
+            matches!(&r, Err(e @ AlphaVantageErr::RequestFailed(_)) if av.is_transient(e) == transient),
+            "{} error result: {:?}",
+            symbol,
+            r
+        );
+    }
+
+    // body that is not json is a permanent failure
+    Mock::given(method("GET"))
+        .and(path("/query"))
+        .and(query_param("symbol", "GARBLED"))
+        .respond_with(ResponseTemplate::new(200).set_body_string("<html>"))
+        .mount(&server)
+        .await;
+    let r = av.get_quotes("GARBLED", None, None, None, None).await;
+    assert!(
+        matches!(&r, Err(e) if !av.is_transient(e)),
+        "error result: {:?}",
+        r
+    );
+}
@@ -0,0 +1,124 @@
+use ndarray::{array, Array1};
+use rs_quant::commons::date::DateTimeError;
+use rs_quant::data::arrow::{read_ipc, write_ipc, ArrowError, FromRecordBatch, ToRecordBatch};
+use rs_quant::data::panel::PricePanel;
+use rs_quant::data::yahoo::QuoteItem;
+use time::macros::{date, datetime};
+use time::OffsetDateTime;
+
+pub fn quotes() -> Array1<QuoteItem> {
+    let quote = |datetime, close: f64| QuoteItem {
+        datetime,
+        timezone: "Europe/Rome".to_string(),
+        open: close - 1.0,
+        high: close + 1.0,
+        low: close - 2.0,
+        close,
+        adjclose: close * 0.99,
+        volume: 1000,
+    };
+    Array1::from_vec(vec![
+        quote(datetime!(2024-02-01 9:00 +1), 100.0),
+        quote(datetime!(2024-02-02 9:00 +1), 101.5),
+        quote(datetime!(2024-07-01 9:00 +2), 99.25),
+    ])
+}
+
+pub fn panel() -> PricePanel {
+    PricePanel::new(
+        vec![date!(2024 - 02 - 01), date!(2024 - 02 - 02)],
+        vec!["AAPL".to_string(), "NVDA".to_string()],
+        array![[100.0, f64::NAN], [101.0, 50.0]],
+    )
+    .unwrap()
+}
+
+pub fn returns() -> Array1<(OffsetDateTime, f64)> {
+    Array1::from_vec(vec![
+        (datetime!(2024-02-02 9:00 +1), 0.015),
+        (datetime!(2024-02-05 9:00 +1), -0.02),
+    ])
+}
+
+// compare panels treating NaN as equal
+pub fn assert_panel_eq(a: &PricePanel, b: &PricePanel) {
+    assert_eq!(a.dates, b.dates);
+    assert_eq!(a.tickers, b.tickers);
+    assert!(a
+        .values
+        .iter()
+        .zip(b.values.iter())
+        .all(|(x, y)| x == y || (x.is_nan() && y.is_nan())));
+}
+
+#[test]
+fn quotes_record_batch() {
+    let batch = quotes().to_record_batch().unwrap();
+    assert_eq!(batch.num_rows(), 3);
+    assert_eq!(batch.num_columns(), 7);
+    assert_eq!(
+        batch.schema().field(0).data_type().to_string(),
+        "Timestamp(Microsecond, Some(\"Europe/Rome\"))"
+    );
+
+    let r = Array1::<QuoteItem>::from_record_batch(&batch).unwrap();
+    assert_eq!(r[2].datetime, datetime!(2024-07-01 9:00 +2));
+    assert_eq!(
+        r[2].datetime.offset(),
+        datetime!(2024-07-01 9:00 +2).offset()
+    );
+    assert_eq!(r[2].timezone, "Europe/Rome");
+    assert_eq!(r[1].adjclose, 101.5 * 0.99);
+    assert_eq!(r[0].volume, 1000);
+}
+
+#[test]
+fn panel_record_batch() {
+    let batch = panel().to_record_batch().unwrap();
+    assert_eq!(batch.schema().field(1).name(), "AAPL");
+    assert_eq!(batch.column(2).null_count(), 1);
+    assert_panel_eq(&PricePanel::from_record_batch(&batch).unwrap(), &panel());
+}
+
+#[test]
+fn missing_column() {
+    let batch = returns().to_record_batch().unwrap();
+    let r = Array1::<QuoteItem>::from_record_batch(&batch);
+    assert!(
+        matches!(r, Err(ArrowError::MissingColumn(ref c)) if c == "open"),
+        "error result: {:?}",
+        r
+    );
+}
+
+#[test]
+fn mixed_timezones() {
+    let mut quotes = quotes();
+    quotes[1].timezone = "America/New_York".to_string();
+    let r = quotes.to_record_batch();
+    assert!(
+        matches!(&r, Err(ArrowError::DateTime(DateTimeError::MixedTimezones(a, b))) if a == "Europe/Rome" && b == "America/New_York"),
+        "error result: {:?}",
+        r
+    );
+}
+
+#[test]
+fn ipc_round_trip() {
+    let dir = std::env::temp_dir();
+
+    let path = dir.join("rs_quant_arrow_quotes.arrow");
+    write_ipc(&path, &quotes()).unwrap();
+    let r: Array1<QuoteItem> = read_ipc(&path).unwrap();
+    assert_eq!(r.len(), 3);
+    assert_eq!(r[0].datetime, quotes()[0].datetime);
+
+    let path = dir.join("rs_quant_arrow_panel.arrow");
+    write_ipc(&path, &panel()).unwrap();
+    assert_panel_eq(&read_ipc(&path).unwrap(), &panel());
+
+    let path = dir.join("rs_quant_arrow_returns.arrow");
+    write_ipc(&path, &returns()).unwrap();
+    let r: Array1<(OffsetDateTime, f64)> = read_ipc(&path).unwrap();
+    assert_eq!(r, returns());
+}
@@ -0,0 +1,66 @@
+use std::time::Duration;
+
+use rs_quant::data::batch::{get_quotes_batch, BatchConfig};
+use rs_quant::data::yahoo::YahooErr;
+use time::macros::date;
+
+use super::common::{Failure, MockProvider};
+
+// provider failing "FLAKY" twice before answering and always failing "BAD"
+fn provider(tickers: &[&str]) -> MockProvider {
+    tickers
+        .iter()
+        .fold(MockProvider::new(), |p, t| {
+            p.with_prices(t, &[(date!(2024 - 02 - 01), 1.0)])
+        })
+        .failing("FLAKY", Failure::Transient(2))
+        .failing("BAD", Failure::Permanent)
+        .with_delay(Duration::from_millis(5))
+}
+
+fn config(concurrency: usize, max_retries: u32) -> BatchConfig {
+    BatchConfig {
+        concurrency,
+        max_retries,
+        backoff: Duration::from_millis(1),
+    }
+}
+
+#[tokio::test]
+async fn partial_failure_report() {
+    let tickers = ["AAPL", "BAD", "FLAKY", "NVDA"];
+    let provider = provider(&tickers);
+    let batch = get_quotes_batch(&provider, &tickers, None, None, None, None, &config(4, 3)).await;
+
+    assert!(!batch.is_complete());
+    assert_eq!(batch.failed_tickers(), vec!["BAD"]);
+    let tickers: Vec<&str> = batch.quotes.iter().map(|(t, _)| t.as_str()).collect();
+    assert_eq!(tickers, vec!["AAPL", "FLAKY", "NVDA"]);
+    assert_eq!(batch.multi_quotes()[0].prices.len(), 3);
+
+    // transient errors are retried, permanent ones are not
+    assert_eq!(provider.attempts("FLAKY"), 3);
+    assert_eq!(provider.attempts("BAD"), 1);
+}
+
+#[tokio::test]
+async fn retries_exhausted() {
+    let provider = provider(&["FLAKY"]);
+    let batch =
+        get_quotes_batch(&provider, &["FLAKY"], None, None, None, None, &config(1, 1)).await;
+    assert!(matches!(batch.errors[0].1, YahooErr::HttpStatus(503, _)));
+    assert_eq!(provider.attempts("FLAKY"), 2);
+}
+
+#[tokio::test]
+async fn concurrency_limit() {
+    let tickers: Vec<String> = (0..12).map(|i| format!("T{}", i)).collect();
+    let tickers: Vec<&str> = tickers.iter().map(String::as_str).collect();
+    let provider = provider(&tickers);
+    let batch = get_quotes_batch(&provider, &tickers, None, None, None, None, &config(3, 0)).await;
+
+    assert!(batch.is_complete());
+    assert_eq!(batch.quotes.len(), 12);
+    let max = provider.max_in_flight();
+    assert!(max > 1 && max <= 3, "max in flight: {}", max);
+}
@@ -0,0 +1,204 @@
+use rs_quant::commons::date::DateTimeError;
+use rs_quant::data::cache::{CacheError, QuoteCache};
+use rs_quant::data::provider::MarketDataProvider;
+use time::{macros::datetime, Date, Month, OffsetDateTime};
+
+use super::common::{quote, MockProvider};
+
+// provider serving one quote per day of february 2024
+fn provider() -> MockProvider {
+    let quotes = (1..=29)
+        .map(|d| {
+            quote(
+                Date::from_calendar_date(2024, Month::February, d).unwrap(),
+                1.0,
+            )
+        })
+        .collect();
+    MockProvider::new().with_quotes("AAPL", quotes)
+}
+
+// date ranges requested to the provider, in arrival order
+fn requested(provider: &MockProvider) -> Vec<(String, String)> {
+    provider
+        .calls()
+        .into_iter()
+        .map(|c| (c.start_date.unwrap(), c.end_date.unwrap()))
+        .collect()
+}
+
+fn cache_dir(name: &str) -> std::path::PathBuf {
+    let dir = std::env::temp_dir().join(name);
+    let _ = std::fs::remove_dir_all(&dir);
+    dir
+}
+
+#[tokio::test]
+async fn serves_overlapping_requests_from_cache() {
+    let cache = QuoteCache::new(provider(), cache_dir("rs_quant_cache_overlap"));
+
+    let q = cache
+        .get_cached_quotes("AAPL", "2024-02-01", "2024-02-10", "1d")
+        .await
+        .unwrap();
+    assert_eq!(q.len(), 10);
+
+    // fully covered range does not reach the provider
+    let q = cache
+        .get_cached_quotes("AAPL", "2024-02-03", "2024-02-05", "1d")
+        .await
+        .unwrap();
+    assert_eq!(q.len(), 3);
+    assert_eq!(q[0].datetime, datetime!(2024-02-03 0:00 UTC));
+
+    // only the missing tail is fetched
+    let q = cache
+        .get_cached_quotes("AAPL", "2024-02-05", "2024-02-15", "1d")
+        .await
+        .unwrap();
+    assert_eq!(q.len(), 11);
+    assert_eq!(
+        requested(&cache.provider),
+        vec![
+            ("2024-02-01".to_string(), "2024-02-10".to_string()),
+            ("2024-02-10".to_string(), "2024-02-15".to_string())
+        ]
+    );
+}
+
+#[tokio::test]
+async fn offline_mode() {
+    let dir = cache_dir("rs_quant_cache_offline");
+    let cache = QuoteCache::new(provider(), &dir);
+    cache
+        .get_quotes(
+            "AAPL",
+            Some("2024-02-01"),
+            Some("2024-02-10"),
+            None,
+            Some("1d"),
+        )
+        .await
+        .unwrap();
+
+    let offline = QuoteCache::new(provider(), &dir).offline(true);
+    let q = offline
+        .get_quotes(
+            "AAPL",
+            Some("2024-02-02"),
+            Some("2024-02-04"),
+            None,
+            Some("1d"),
+        )
+        .await
+        .unwrap();
+    assert_eq!(q.len(), 3);
+
+    let r = offline
+        .get_quotes(
+            "AAPL",
+            Some("2024-02-02"),
+            Some("2024-02-20"),
+            None,
+            Some("1d"),
+        )
+        .await;
+    assert!(
+        matches!(r, Err(CacheError::NotCached(_))),
+        "error result: {:?}",
+        r
+    );
+    let r = offline
+        .get_quotes("MSFT", None, None, Some("5d"), None)
+        .await;
+    assert!(
+        matches!(r, Err(CacheError::NotCached(_))),
+        "error result: {:?}",
+        r
+    );
+    assert!(offline.provider.calls().is_empty());
+}
+
+#[tokio::test]
+async fn invalid_date_and_invalidate() {
+    let cache = QuoteCache::new(provider(), cache_dir("rs_quant_cache_invalidate"));
+    let r = cache
+        .get_cached_quotes("AAPL", "invalid-date", "2024-02-10", "1d")
+        .await;
+    assert!(
+        matches!(
+            r,
+            Err(CacheError::InvalidDateFormat(
+                DateTimeError::InvalidDateFormat(..)
+            ))
+        ),
+        "error result: {:?}",
+        r
+    );
+
+    cache
+        .get_cached_quotes("AAPL", "2024-02-01", "2024-02-02", "1d")
+        .await
+        .unwrap();
+    cache.invalidate("AAPL", "1d").unwrap();
+    cache
+        .get_cached_quotes("AAPL", "2024-02-01", "2024-02-02", "1d")
+        .await
+        .unwrap();
+    assert_eq!(cache.provider.calls().len(), 2);
+}
+
+#[tokio::test]
+async fn refetches_current_day() {
+    let cache = QuoteCache::new(provider(), cache_dir("rs_quant_cache_same_day"));
+    let today = OffsetDateTime::now_utc().date().to_string();
+
+    // today's session is never considered closed, nothing is stored and the request is repeated
+    for _ in 0..2 {
+        cache
+            .get_cached_quotes("AAPL", &today, &today, "1d")
+            .await
+            .unwrap();
+    }
+    assert_eq!(
+        requested(&cache.provider),
+        vec![(today.clone(), today.clone()), (today.clone(), today)]
+    );
+    assert!(!cache.dir.join("AAPL_1d.json").exists());
+}
+
+#[tokio::test]
+async fn keeps_disjoint_segments() {
+    let cache = QuoteCache::new(provider(), cache_dir("rs_quant_cache_segments"));
+    cache
+        .get_cached_quotes("AAPL", "2024-02-01", "2024-02-03", "1m")
+        .await
+        .unwrap();
+
+    // a request past the cached range fetches only its own range
+    let q = cache
+        .get_cached_quotes("AAPL", "2024-02-20", "2024-02-21", "1m")
+        .await
+        .unwrap();
+    assert_eq!(q.len(), 2);
+
+    // both segments are kept and a bridging request only fetches the gap between them
+    let q = cache
+        .get_cached_quotes("AAPL", "2024-02-02", "2024-02-21", "1m")
+        .await
+        .unwrap();
+    assert_eq!(q.len(), 20);
+    let q = cache
+        .get_cached_quotes("AAPL", "2024-02-01", "2024-02-21", "1m")
+        .await
+        .unwrap();
+    assert_eq!(q.len(), 21);
+    assert_eq!(
+        requested(&cache.provider),
+        vec![
+            ("2024-02-01".to_string(), "2024-02-03".to_string()),
+            ("2024-02-20".to_string(), "2024-02-21".to_string()),
+            ("2024-02-03".to_string(), "2024-02-20".to_string())
+        ]
+    );
+}
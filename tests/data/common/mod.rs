@@ -0,0 +1,220 @@
+pub mod yahoo;
+
+use std::collections::{HashMap, VecDeque};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+use std::time::Duration;
+
+use ndarray::Array1;
+use rs_quant::commons::date::{parse_date, DateType};
+use rs_quant::data::provider::MarketDataProvider;
+use rs_quant::data::yahoo::{MultiQuoteItem, OptionContract, OptionType, QuoteItem, YahooErr};
+use time::Date;
+
+// failure injected into the quote requests of a symbol
+#[derive(Debug, Clone, Copy)]
+pub enum Failure {
+    // the first `n` requests fail with a transient error, the following ones are answered
+    Transient(usize),
+    // every request fails with a permanent error
+    Permanent,
+}
+
+// quote request received by the provider
+#[derive(Debug, Clone, PartialEq)]
+pub struct QuoteCall {
+    pub symbol: String,
+    pub start_date: Option<String>,
+    pub end_date: Option<String>,
+}
+
+// deterministic in-memory provider used to test code without hitting the network
+// quotes are served from fixed series, latest quotes can be scripted and failures injected,
+// every quote request is recorded together with the number of requests in flight
+#[derive(Default)]
+pub struct MockProvider {
+    quotes: HashMap<String, Vec<QuoteItem>>,
+    latest: Mutex<HashMap<String, VecDeque<Option<f64>>>>,
+    failures: HashMap<String, Failure>,
+    delay: Duration,
+    calls: Mutex<Vec<QuoteCall>>,
+    in_flight: AtomicUsize,
+    max_in_flight: AtomicUsize,
+}
+
+// transient error returned by scripted failures
+fn unavailable() -> YahooErr {
+    YahooErr::HttpStatus(503, "service unavailable".to_string())
+}
+
+// daily bar at utc midnight with every price set to `price`
+pub fn quote(date: Date, price: f64) -> QuoteItem {
+    QuoteItem {
+        datetime: date.midnight().assume_utc(),
+        timezone: "UTC".to_string(),
+        open: price,
+        high: price,
+        low: price,
+        close: price,
+        adjclose: price,
+        volume: 0,
+    }
+}
+
+impl MockProvider {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // serve `quotes` for `symbol`, date range requests only get the bars falling into the range
+    pub fn with_quotes(mut self, symbol: &str, quotes: Vec<QuoteItem>) -> Self {
+        self.quotes.insert(symbol.to_string(), quotes);
+        self
+    }
+
+    // serve one daily bar per (date, price) pair for `symbol`
+    pub fn with_prices(self, symbol: &str, prices: &[(Date, f64)]) -> Self {
+        let quotes = prices.iter().map(|(d, p)| quote(*d, *p)).collect();
+        self.with_quotes(symbol, quotes)
+    }
+
+    // script the latest quotes of `symbol`: `None` fails the poll transiently,
+    // the last entry is repeated forever
+    pub fn with_latest(self, symbol: &str, prices: &[Option<f64>]) -> Self {
+        self.latest
+            .lock()
+            .unwrap()
+            .insert(symbol.to_string(), prices.iter().copied().collect());
+        self
+    }
+
+    // inject a failure into the quote requests of `symbol`
+    pub fn failing(mut self, symbol: &str, failure: Failure) -> Self {
+        self.failures.insert(symbol.to_string(), failure);
+        self
+    }
+
+    // wait `delay` before answering every quote request
+    pub fn with_delay(mut self, delay: Duration) -> Self {
+        self.delay = delay;
+        self
+    }
+
+    // every quote request received so far, in arrival order
+    pub fn calls(&self) -> Vec<QuoteCall> {
+        self.calls.lock().unwrap().clone()
+    }
+
+    // number of quote requests received for `symbol`
+    pub fn attempts(&self, symbol: &str) -> usize {
+        self.calls
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|c| c.symbol == symbol)
+            .count()
+    }
+
+    // highest number of quote requests served at the same time
+    pub fn max_in_flight(&self) -> usize {
+        self.max_in_flight.load(Ordering::SeqCst)
+    }
+}
+
+impl MarketDataProvider for MockProvider {
+    type Error = YahooErr;
+
+    async fn get_quotes(
+        &self,
+        symbol: &str,
+        start_date: Option<&str>,
+        end_date: Option<&str>,
+        _period: Option<&str>,
+        _interval: Option<&str>,
+    ) -> Result<Array1<QuoteItem>, YahooErr> {
+        self.calls.lock().unwrap().push(QuoteCall {
+            symbol: symbol.to_string(),
+            start_date: start_date.map(str::to_string),
+            end_date: end_date.map(str::to_string),
+        });
+        let attempt = self.attempts(symbol);
+        let n = self.in_flight.fetch_add(1, Ordering::SeqCst) + 1;
+        self.max_in_flight.fetch_max(n, Ordering::SeqCst);
+        tokio::time::sleep(self.delay).await;
+        self.in_flight.fetch_sub(1, Ordering::SeqCst);
+
+        match self.failures.get(symbol) {
+            Some(Failure::Permanent) => return Err(YahooErr::EmptyDataSet),
+            Some(Failure::Transient(n)) if attempt <= *n => return Err(unavailable()),
+            _ => {}
+        }
+
+        let start = start_date.map(|d| parse_date(d, DateType::Start).unwrap().date());
+        let end = end_date.map(|d| parse_date(d, DateType::End).unwrap().date());
+        Ok(self
+            .quotes
+            .get(symbol)
+            .ok_or(YahooErr::EmptyDataSet)?
+            .iter()
+            .filter(|q| start.is_none_or(|s| s <= q.datetime.date()))
+            .filter(|q| end.is_none_or(|e| q.datetime.date() <= e))
+            .cloned()
+            .collect())
+    }
+
+    async fn get_multiple_quotes(
+        &self,
+        tickers: Vec<&str>,
+        start_date: Option<&str>,
+        end_date: Option<&str>,
+        period: Option<&str>,
+        interval: Option<&str>,
+    ) -> Result<Array1<MultiQuoteItem>, YahooErr> {
+        let mut date_map: HashMap<Date, HashMap<String, f64>> = HashMap::new();
+        for t in tickers {
+            let quotes = self
+                .get_quotes(t, start_date, end_date, period, interval)
+                .await?;
+            for q in quotes {
+                date_map
+                    .entry(q.datetime.date())
+                    .or_default()
+                    .insert(t.to_string(), q.adjclose);
+            }
+        }
+        let mut m_quotes: Vec<MultiQuoteItem> = date_map
+            .into_iter()
+            .map(|(date, prices)| MultiQuoteItem { date, prices })
+            .collect();
+        m_quotes.sort_by_key(|q| q.date);
+        Ok(Array1::from_vec(m_quotes))
+    }
+
+    async fn get_latest_quote(&self, ticker: &str) -> Result<f64, YahooErr> {
+        if let Some(script) = self.latest.lock().unwrap().get_mut(ticker) {
+            let price = if script.len() > 1 {
+                script.pop_front().unwrap()
+            } else {
+                script[0]
+            };
+            return price.ok_or_else(unavailable);
+        }
+        self.quotes
+            .get(ticker)
+            .and_then(|q| q.last())
+            .map(|q| q.close)
+            .ok_or(YahooErr::EmptyDataSet)
+    }
+
+    async fn get_options(
+        &self,
+        _ticker: &str,
+        _option_type: OptionType,
+    ) -> Result<Array1<OptionContract>, YahooErr> {
+        Err(YahooErr::EmptyDataSet)
+    }
+
+    fn is_transient(&self, error: &YahooErr) -> bool {
+        matches!(error, YahooErr::HttpStatus(503, _))
+    }
+}
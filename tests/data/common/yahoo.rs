@@ -0,0 +1,33 @@
+use rs_quant::data::yahoo::Yahoo;
+use wiremock::matchers::{header, method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+// start a mock server handing out the session cookie and crumb
+pub async fn mock_server() -> MockServer {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/"))
+        .respond_with(ResponseTemplate::new(404).insert_header("set-cookie", "A3=d=test; Path=/"))
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/v1/test/getcrumb"))
+        .and(header("cookie", "A3=d=test"))
+        .respond_with(ResponseTemplate::new(200).set_body_string("test-crumb"))
+        .mount(&server)
+        .await;
+    server
+}
+
+// answer requests to `endpoint` with the given status and body
+pub async fn mount(server: &MockServer, endpoint: &str, status: u16, body: &str) {
+    Mock::given(method("GET"))
+        .and(path(endpoint))
+        .respond_with(ResponseTemplate::new(status).set_body_string(body))
+        .mount(server)
+        .await;
+}
+
+pub fn provider(server: &MockServer) -> Yahoo {
+    Yahoo::provider().unwrap().with_base_url(&server.uri())
+}
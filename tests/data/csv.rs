@@ -0,0 +1,232 @@
+use std::collections::HashMap;
+
+use ndarray::array;
+use rs_quant::data::csv::{
+    read_multi_quotes, read_multi_quotes_from_reader, read_quotes, read_quotes_from_reader,
+    write_multi_quotes, write_multi_quotes_to_writer, write_quotes, write_quotes_to_writer,
+    CsvColumns, CsvConfig, CsvError,
+};
+use rs_quant::data::yahoo::{MultiQuoteItem, QuoteItem};
+use time::macros::{date, datetime};
+
+const SINGLE: &str = "Date,Open,High,Low,Close,Adj Close,Volume
+2024-02-02,102.0,106.0,98.0,104.0,103.5,1100
+2024-02-01,100.0,105.0,95.0,102.0,101.5,1000
+";
+
+#[test]
+fn read_single_asset() {
+    let q = read_quotes_from_reader(SINGLE.as_bytes(), &CsvConfig::default()).unwrap();
+    assert_eq!(q.len(), 2);
+
+    // rows are sorted by date
+    assert_eq!(q[0].datetime, datetime!(2024-02-01 0:00 UTC));
+    assert_eq!(q[0].timezone, "UTC");
+    assert_eq!(q[0].open, 100.0);
+    assert_eq!(q[0].adjclose, 101.5);
+    assert_eq!(q[0].volume, 1000);
+    assert_eq!(q[1].datetime, datetime!(2024-02-02 0:00 UTC));
+    assert_eq!(q[1].close, 104.0);
+}
+
+#[test]
+fn read_custom_mapping() {
+    let data = "day;o;h;l;c;v\n01/02/2024;100.5;101;99;100.25;500\n";
+    let config = CsvConfig {
+        delimiter: b';',
+        date_format: "[day]/[month]/[year]".to_string(),
+        timezone: "Europe/Rome".to_string(),
+        columns: CsvColumns {
+            datetime: "day".to_string(),
+            open: "o".to_string(),
+            high: "h".to_string(),
+            low: "l".to_string(),
+            close: "c".to_string(),
+            adjclose: "ac".to_string(),
+            volume: "v".to_string(),
+        },
+    };
+    let q = read_quotes_from_reader(data.as_bytes(), &config).unwrap();
+    assert_eq!(q[0].datetime, datetime!(2024-02-01 0:00 +1));
+    assert_eq!(q[0].timezone, "Europe/Rome");
+    assert_eq!(q[0].open, 100.5);
+    // missing adjusted close column falls back to close
+    assert_eq!(q[0].adjclose, 100.25);
+    assert_eq!(q[0].volume, 500);
+}
+
+#[test]
+fn read_intraday_datetime() {
+    let data = "Date,Open,High,Low,Close\n2024-02-01 09:30:00,1,2,0.5,1.5\n";
+    let config = CsvConfig {
+        date_format: "[year]-[month]-[day] [hour]:[minute]:[second]".to_string(),
+        ..CsvConfig::default()
+    };
+    let q = read_quotes_from_reader(data.as_bytes(), &config).unwrap();
+    assert_eq!(q[0].datetime, datetime!(2024-02-01 09:30:00 UTC));
+    assert_eq!(q[0].volume, 0);
+}
+
+#[test]
+fn missing_column() {
+    let data = "Date,Open,High,Low\n2024-02-01,1,2,0.5\n";
+    let r = read_quotes_from_reader(data.as_bytes(), &CsvConfig::default());
+    assert!(
+        matches!(&r, Err(CsvError::MissingColumn(c)) if c == "Close"),
+        "error result: {:?}",
+        r
+    );
+}
+
+#[test]
+fn malformed_row() {
+    let data = "Date,Open,High,Low,Close\n2024-02-01,1,2,0.5,1.5\n2024-02-02,1,null,0.5,1.5\n";
+    let r = read_quotes_from_reader(data.as_bytes(), &CsvConfig::default());
+    assert!(
+        matches!(&r, Err(CsvError::MalformedRow { row: 3, column, value, source }) if column == "High" && value == "null" && source.is::<std::num::ParseFloatError>()),
+        "error result: {:?}",
+        r
+    );
+
+    let data = "Date,Open,High,Low,Close\n02-01-2024,1,2,0.5,1.5\n";
+    let r = read_quotes_from_reader(data.as_bytes(), &CsvConfig::default());
+    assert!(
+        matches!(&r, Err(CsvError::MalformedRow { row: 2, column, .. }) if column == "Date"),
+        "error result: {:?}",
+        r
+    );
+}
+
+#[test]
+fn empty_and_invalid_format() {
+    let r = read_quotes_from_reader(
+        "Date,Open,High,Low,Close\n".as_bytes(),
+        &CsvConfig::default(),
+    );
+    assert!(
+        matches!(r, Err(CsvError::EmptyDataSet)),
+        "error result: {:?}",
+        r
+    );
+
+    let config = CsvConfig {
+        date_format: "[year".to_string(),
+        ..CsvConfig::default()
+    };
+    let r = read_quotes_from_reader(SINGLE.as_bytes(), &config);
+    assert!(
+        matches!(r, Err(CsvError::InvalidDateFormat(_, _))),
+        "error result: {:?}",
+        r
+    );
+
+    let config = CsvConfig {
+        timezone: "Mars/Olympus_Mons".to_string(),
+        ..CsvConfig::default()
+    };
+    let r = read_quotes_from_reader(SINGLE.as_bytes(), &config);
+    assert!(
+        matches!(r, Err(CsvError::UnknownTimezone(_))),
+        "error result: {:?}",
+        r
+    );
+}
+
+#[test]
+fn read_wide_multi_asset() {
+    let data = "Date,AAPL,NVDA\n2024-02-01,150.0,600.0\n2024-02-02,155.0,\n";
+    let m = read_multi_quotes_from_reader(data.as_bytes(), &CsvConfig::default()).unwrap();
+    assert_eq!(m.len(), 2);
+    assert_eq!(m[0].date, date!(2024 - 02 - 01));
+    assert_eq!(
+        m[0].prices,
+        HashMap::from([("AAPL".to_string(), 150.0), ("NVDA".to_string(), 600.0)])
+    );
+    // empty cell is a missing price
+    assert_eq!(m[1].prices, HashMap::from([("AAPL".to_string(), 155.0)]));
+
+    let data = "Date,AAPL\n2024-02-01,abc\n";
+    let r = read_multi_quotes_from_reader(data.as_bytes(), &CsvConfig::default());
+    assert!(
+        matches!(&r, Err(CsvError::MalformedRow { column, .. }) if column == "AAPL"),
+        "error result: {:?}",
+        r
+    );
+}
+
+#[test]
+fn write_single_asset() {
+    let quotes = array![QuoteItem {
+        datetime: datetime!(2024-02-01 0:00 UTC),
+        timezone: "UTC".to_string(),
+        open: 100.0,
+        high: 105.5,
+        low: 95.0,
+        close: 102.0,
+        adjclose: 101.5,
+        volume: 1000,
+    }];
+    let config = CsvConfig {
+        delimiter: b';',
+        date_format: "[day]/[month]/[year]".to_string(),
+        ..CsvConfig::default()
+    };
+    let mut buf = Vec::new();
+    write_quotes_to_writer(&mut buf, &quotes, &config).unwrap();
+    assert_eq!(
+        String::from_utf8(buf).unwrap(),
+        "Date;Open;High;Low;Close;Adj Close;Volume\n01/02/2024;100;105.5;95;102;101.5;1000\n"
+    );
+
+    // a valid description asking for components the value does not have fails at format time
+    let multi = array![MultiQuoteItem {
+        date: date!(2024 - 02 - 01),
+        prices: HashMap::from([("AAPL".to_string(), 150.0)]),
+    }];
+    let config = CsvConfig {
+        date_format: "[year]-[month]-[day] [offset_hour]".to_string(),
+        ..CsvConfig::default()
+    };
+    let r = write_multi_quotes_to_writer(Vec::new(), &multi, &config);
+    assert!(
+        matches!(r, Err(CsvError::FormatFailed(_, _))),
+        "error result: {:?}",
+        r
+    );
+}
+
+#[test]
+fn round_trip_files() {
+    let dir = std::env::temp_dir();
+    let config = CsvConfig::default();
+
+    let quotes = read_quotes_from_reader(SINGLE.as_bytes(), &config).unwrap();
+    let path = dir.join("rs_quant_csv_single.csv");
+    write_quotes(&path, &quotes, &config).unwrap();
+    let r = read_quotes(&path, &config).unwrap();
+    assert_eq!(r.len(), quotes.len());
+    assert_eq!(r[1].datetime, quotes[1].datetime);
+    assert_eq!(r[1].adjclose, quotes[1].adjclose);
+
+    let multi = array![
+        MultiQuoteItem {
+            date: date!(2024 - 02 - 01),
+            prices: HashMap::from([("AAPL".to_string(), 150.0), ("GOOG".to_string(), 2800.0)]),
+        },
+        MultiQuoteItem {
+            date: date!(2024 - 02 - 02),
+            prices: HashMap::from([("GOOG".to_string(), 2856.0)]),
+        }
+    ];
+    let path = dir.join("rs_quant_csv_multi.csv");
+    write_multi_quotes(&path, &multi, &config).unwrap();
+    assert_eq!(
+        std::fs::read_to_string(&path).unwrap(),
+        "Date,AAPL,GOOG\n2024-02-01,150,2800\n2024-02-02,,2856\n"
+    );
+    let r = read_multi_quotes(&path, &config).unwrap();
+    for (r, m) in r.iter().zip(multi.iter()) {
+        assert_eq!(r.date, m.date);
+        assert_eq!(r.prices, m.prices);
+    }
+}
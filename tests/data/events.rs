@@ -0,0 +1,77 @@
+use ndarray::{array, Array1};
+use rs_quant::data::events::{adjusted_close, DividendItem, SplitItem};
+use rs_quant::data::yahoo::QuoteItem;
+use rs_quant::{assert_approx, commons::utils::EPSILON};
+use time::macros::date;
+use time::Date;
+
+fn quotes(closes: &[(Date, f64)]) -> Array1<QuoteItem> {
+    closes
+        .iter()
+        .map(|(d, c)| QuoteItem {
+            datetime: d.midnight().assume_utc(),
+            timezone: "UTC".to_string(),
+            open: *c,
+            high: *c,
+            low: *c,
+            close: *c,
+            adjclose: *c,
+            volume: 0,
+        })
+        .collect()
+}
+
+#[test]
+fn split_ratio() {
+    let s = SplitItem {
+        date: date!(2020 - 08 - 31),
+        numerator: 4.0,
+        denominator: 1.0,
+    };
+    assert_eq!(s.ratio(), 4.0);
+}
+
+#[test]
+fn adjusted_close_without_events() {
+    let q = quotes(&[
+        (date!(2024 - 02 - 01), 100.0),
+        (date!(2024 - 02 - 02), 101.0),
+    ]);
+    assert_eq!(
+        adjusted_close(&q, &array![], &array![]),
+        array![100.0, 101.0]
+    );
+}
+
+#[test]
+fn adjusted_close_with_split_and_dividend() {
+    let q = quotes(&[
+        (date!(2024 - 02 - 01), 400.0),
+        (date!(2024 - 02 - 02), 410.0),
+        (date!(2024 - 02 - 05), 104.0),
+        (date!(2024 - 02 - 07), 100.0),
+        (date!(2024 - 02 - 08), 101.0),
+    ]);
+    // 4:1 split on 2024-02-05 and 2.0 dividend with ex-date on a day without quote (applies from 2024-02-07)
+    let splits = array![SplitItem {
+        date: date!(2024 - 02 - 05),
+        numerator: 4.0,
+        denominator: 1.0,
+    }];
+    let dividends = array![DividendItem {
+        date: date!(2024 - 02 - 06),
+        amount: 2.0,
+    }];
+
+    let adj = adjusted_close(&q, &array![], &splits);
+    assert_eq!(adj, array![100.0, 102.5, 104.0, 100.0, 101.0]);
+
+    let adj = adjusted_close(&q, &dividends, &splits);
+    // dividend factor from 2024-02-07 backwards uses previous close: 1 - 2 / 104
+    let f = 1.0 - 2.0 / 104.0;
+    assert_approx!(adj[4], 101.0, EPSILON);
+    assert_approx!(adj[3], 100.0, EPSILON);
+    assert_approx!(adj[2], 104.0 * f, EPSILON);
+    assert_approx!(adj[1], 102.5 * f, EPSILON);
+    assert_approx!(adj[0], 100.0 * f, EPSILON);
+}
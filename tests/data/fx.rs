@@ -0,0 +1,228 @@
+use ndarray::Array1;
+use rs_quant::commons::format::Round;
+use rs_quant::data::fx::{fx_symbol, FxError, FxRates};
+use rs_quant::data::yahoo::{QuoteItem, Yahoo};
+use rs_quant::portfolio::portfolio::Portfolio;
+use rs_quant::portfolio::position::Position;
+use time::macros::{date, datetime};
+use time::Duration;
+use wiremock::matchers::{method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+// USD pivoted rates: 1 EUR = 1.10 / 1.20 USD, 1 GBP = 1.25 USD
+fn rates() -> FxRates {
+    let mut rates = FxRates::new("usd");
+    rates.insert("EUR", "USD", date!(2024 - 01 - 02), 1.10);
+    rates.insert("EUR", "USD", date!(2024 - 02 - 01), 1.20);
+    rates.insert("GBP", "USD", date!(2024 - 01 - 02), 1.25);
+    rates.insert("GBP", "USD", date!(2024 - 02 - 01), 1.25);
+    rates
+}
+
+#[test]
+fn symbol() {
+    assert_eq!(fx_symbol("eur", "USD"), "EURUSD=X");
+}
+
+#[test]
+fn direct_and_inverse_rate() {
+    let rates = rates();
+    assert_eq!(
+        rates.rate("EUR", "USD", date!(2024 - 01 - 02)).unwrap(),
+        1.10
+    );
+    assert_eq!(
+        rates.rate("usd", "gbp", date!(2024 - 01 - 02)).unwrap(),
+        0.8
+    );
+    assert_eq!(
+        rates.rate("EUR", "EUR", date!(2020 - 01 - 01)).unwrap(),
+        1.0
+    );
+}
+
+#[test]
+fn rate_falls_back_to_previous_date() {
+    let rates = rates();
+    assert_eq!(
+        rates.rate("EUR", "USD", date!(2024 - 01 - 09)).unwrap(),
+        1.10
+    );
+    assert_eq!(
+        rates.rate("EUR", "USD", date!(2024 - 02 - 03)).unwrap(),
+        1.20
+    );
+    assert_eq!(rates.latest_rate("EUR", "USD").unwrap(), 1.20);
+}
+
+#[test]
+fn stale_rate() {
+    let rates = rates();
+    assert!(matches!(
+        rates.rate("EUR", "USD", date!(2024 - 01 - 10)),
+        Err(FxError::StaleRate(from, to, d)) if from == "EUR" && to == "USD" && d == date!(2024 - 01 - 10)
+    ));
+    assert!(matches!(
+        rates.rate("USD", "GBP", date!(2024 - 01 - 31)),
+        Err(FxError::StaleRate(_, _, _))
+    ));
+
+    let rates = rates.with_max_lookback(Duration::days(31));
+    assert_eq!(
+        rates.rate("EUR", "USD", date!(2024 - 01 - 31)).unwrap(),
+        1.10
+    );
+}
+
+#[test]
+fn negative_lookback() {
+    // clamped to zero: only the rate of the date itself is used
+    let rates = rates().with_max_lookback(Duration::days(-3));
+    assert_eq!(rates.max_lookback, Duration::ZERO);
+    assert_eq!(
+        rates.rate("EUR", "USD", date!(2024 - 01 - 02)).unwrap(),
+        1.10
+    );
+    assert!(matches!(
+        rates.rate("EUR", "USD", date!(2024 - 01 - 03)),
+        Err(FxError::StaleRate(..))
+    ));
+
+    // set directly on the public field
+    let mut rates = rates;
+    rates.max_lookback = Duration::days(-3);
+    assert_eq!(
+        rates.rate("EUR", "USD", date!(2024 - 02 - 01)).unwrap(),
+        1.20
+    );
+}
+
+#[test]
+fn cross_rate() {
+    let rates = rates();
+    assert_eq!(
+        rates
+            .rate("EUR", "GBP", date!(2024 - 02 - 01))
+            .unwrap()
+            .rounded(4),
+        0.96
+    );
+    assert_eq!(
+        rates
+            .convert(100.0, "GBP", "EUR", date!(2024 - 01 - 02))
+            .unwrap()
+            .rounded(4),
+        113.6364
+    );
+}
+
+#[test]
+fn missing_rate() {
+    let rates = rates();
+    assert!(matches!(
+        rates.rate("EUR", "USD", date!(2023 - 12 - 31)),
+        Err(FxError::MissingRate(_, _))
+    ));
+    assert!(matches!(
+        rates.rate("CHF", "USD", date!(2024 - 01 - 02)),
+        Err(FxError::MissingRate(from, to)) if from == "CHF" && to == "USD"
+    ));
+}
+
+#[test]
+fn convert_quotes() {
+    let quote = |datetime, price| QuoteItem {
+        datetime,
+        timezone: "UTC".to_string(),
+        open: price,
+        high: price,
+        low: price,
+        close: price,
+        adjclose: price,
+        volume: 10,
+    };
+    let quotes = Array1::from_vec(vec![
+        quote(datetime!(2024-01-05 16:00 UTC), 10.0),
+        quote(datetime!(2024-02-01 16:00 UTC), 10.0),
+    ]);
+    let converted = rates().convert_quotes(&quotes, "EUR", "USD").unwrap();
+    assert_eq!(converted[0].close, 11.0);
+    assert_eq!(converted[1].adjclose, 12.0);
+    assert_eq!(converted[1].volume, 10);
+}
+
+#[tokio::test]
+async fn fetch_rates() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/v8/finance/chart/EURUSD=X"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_string(include_str!("../fixtures/yahoo/chart_eurusd.json")),
+        )
+        .expect(1)
+        .mount(&server)
+        .await;
+    let provider = Yahoo::provider().unwrap().with_base_url(&server.uri());
+
+    let rates = FxRates::fetch(
+        &provider,
+        &["EUR", "USD", "eur"],
+        "USD",
+        "2024-02-01",
+        "2024-02-02",
+    )
+    .await
+    .unwrap();
+    assert_eq!(
+        rates.rate("EUR", "USD", date!(2024 - 02 - 01)).unwrap(),
+        1.0812
+    );
+    assert_eq!(rates.latest_rate("EUR", "USD").unwrap(), 1.0786);
+}
+
+fn portfolio() -> Portfolio {
+    let mut eur = Position::new("VUAA.MI", 10.0, "EUR", "2024-01-02", 100.0, 1000.0);
+    eur.market_price = 110.0;
+    eur.market_value = 1100.0;
+    let mut usd = Position::new("AAPL", 10.0, "USD", "2024-01-02", 150.0, 1500.0);
+    usd.market_price = 180.0;
+    usd.market_value = 1800.0;
+    Portfolio {
+        positions: Array1::from_vec(vec![eur, usd]),
+    }
+}
+
+#[test]
+fn position_to_currency() {
+    let p = portfolio().positions[0]
+        .to_currency(&rates(), "USD", date!(2024 - 02 - 01))
+        .unwrap();
+    assert_eq!(p.currency, "USD");
+    assert_eq!(p.buy_value.rounded(4), 1100.0);
+    assert_eq!(p.market_value.rounded(4), 1320.0);
+    assert_eq!(p.pl.rounded(4), 220.0);
+    assert_eq!(p.pl_pct.rounded(4), 0.2);
+}
+
+#[test]
+fn portfolio_to_currency() {
+    let p = portfolio();
+    assert_eq!(
+        p.total_mkt_val_in(&rates(), "USD", date!(2024 - 02 - 01))
+            .unwrap()
+            .rounded(4),
+        3120.0
+    );
+
+    let usd = p
+        .to_currency(&rates(), "USD", date!(2024 - 02 - 01))
+        .unwrap();
+    assert!(usd.positions.iter().all(|p| p.currency == "USD"));
+    assert_eq!(usd.total_mkt_val().rounded(4), 3120.0);
+    assert_eq!(usd.positions[1].weight.rounded(4), 0.5769);
+    assert!(matches!(
+        p.to_currency(&rates(), "CHF", date!(2024 - 02 - 01)),
+        Err(FxError::MissingRate(_, _))
+    ));
+}
@@ -0,0 +1,116 @@
+use ndarray::Array1;
+use rs_quant::data::options::{OptionChain, OptionExpiry, OptionFilter};
+use rs_quant::data::yahoo::{OptionContract, OptionType};
+use time::macros::date;
+
+fn contract(strike: f64, in_the_money: bool) -> OptionContract {
+    OptionContract {
+        contract_symbol: Some(format!("TEST{}", strike)),
+        strike: Some(strike),
+        currency: Some("USD".to_string()),
+        last_price: None,
+        change: None,
+        percent_change: None,
+        volume: None,
+        open_interest: None,
+        bid: None,
+        ask: None,
+        contract_size: Some("REGULAR".to_string()),
+        expiration: None,
+        last_trade_date: None,
+        implied_volatility: None,
+        in_the_money: Some(in_the_money),
+    }
+}
+
+fn chain() -> OptionChain {
+    let expiry = |expiration| OptionExpiry {
+        expiration,
+        calls: Array1::from_vec(vec![
+            contract(90.0, true),
+            contract(100.0, false),
+            contract(110.0, false),
+        ]),
+        puts: Array1::from_vec(vec![
+            contract(90.0, false),
+            contract(100.0, false),
+            contract(110.0, true),
+        ]),
+    };
+    OptionChain {
+        underlying_symbol: "TEST".to_string(),
+        underlying_price: Some(100.0),
+        currency: Some("USD".to_string()),
+        expiries: vec![
+            expiry(date!(2025 - 01 - 17)),
+            expiry(date!(2025 - 02 - 21)),
+            expiry(date!(2025 - 03 - 21)),
+        ],
+    }
+}
+
+#[test]
+fn contracts() {
+    let chain = chain();
+    assert_eq!(chain.contracts(OptionType::Call).len(), 9);
+    assert_eq!(chain.contracts(OptionType::Put).len(), 9);
+}
+
+#[test]
+fn filter_expiration() {
+    let filtered = chain().filter(&OptionFilter {
+        min_expiration: Some(date!(2025 - 02 - 01)),
+        max_expiration: Some(date!(2025 - 02 - 28)),
+        ..Default::default()
+    });
+    assert_eq!(filtered.expirations(), vec![date!(2025 - 02 - 21)]);
+}
+
+#[test]
+fn filter_strike_and_moneyness() {
+    let by_strike = chain().filter(&OptionFilter {
+        min_strike: Some(95.0),
+        max_strike: Some(105.0),
+        ..Default::default()
+    });
+    let by_moneyness = chain().filter(&OptionFilter {
+        min_moneyness: Some(0.95),
+        max_moneyness: Some(1.05),
+        ..Default::default()
+    });
+    for filtered in [by_strike, by_moneyness] {
+        let strikes: Vec<Option<f64>> = filtered
+            .contracts(OptionType::Call)
+            .iter()
+            .map(|c| c.strike)
+            .collect();
+        assert_eq!(strikes, vec![Some(100.0); 3]);
+    }
+}
+
+#[test]
+fn filter_in_the_money() {
+    let filtered = chain().filter(&OptionFilter {
+        in_the_money: Some(true),
+        ..Default::default()
+    });
+    assert_eq!(filtered.expiries.len(), 3);
+    assert!(filtered
+        .expiries
+        .iter()
+        .all(|e| e.calls[0].strike == Some(90.0)
+            && e.puts[0].strike == Some(110.0)
+            && e.calls.len() == 1
+            && e.puts.len() == 1));
+}
+
+#[test]
+fn filter_moneyness_without_spot() {
+    let mut chain = chain();
+    chain.underlying_price = None;
+    let filtered = chain.filter(&OptionFilter {
+        min_moneyness: Some(0.9),
+        ..Default::default()
+    });
+    assert!(filtered.contracts(OptionType::Call).is_empty());
+}
@@ -0,0 +1,131 @@
+use std::collections::HashMap;
+
+use ndarray::{array, Array1};
+use rs_quant::commons::format::Round;
+use rs_quant::data::panel::{PanelError, PricePanel};
+use rs_quant::data::yahoo::MultiQuoteItem;
+use rs_quant::quantitative::returns::ReturnType;
+use time::macros::date;
+
+fn multi_quotes() -> Array1<MultiQuoteItem> {
+    let row = |date, prices: &[(&str, f64)]| MultiQuoteItem {
+        date,
+        prices: prices
+            .iter()
+            .map(|(t, p)| (t.to_string(), *p))
+            .collect::<HashMap<String, f64>>(),
+    };
+    Array1::from_vec(vec![
+        row(date!(2024 - 02 - 02), &[("NVDA", 55.0), ("AAPL", 110.0)]),
+        row(date!(2024 - 02 - 01), &[("NVDA", 50.0), ("AAPL", 100.0)]),
+        row(date!(2024 - 02 - 05), &[("AAPL", 121.0)]),
+    ])
+}
+
+#[test]
+fn from_multi_quotes() {
+    let panel = PricePanel::from(&multi_quotes());
+    assert_eq!(panel.tickers, vec!["AAPL", "NVDA"]);
+    assert_eq!(
+        panel.dates,
+        vec![
+            date!(2024 - 02 - 01),
+            date!(2024 - 02 - 02),
+            date!(2024 - 02 - 05)
+        ]
+    );
+    assert_eq!(panel.dim(), (3, 2));
+    assert_eq!(panel.column("AAPL").unwrap(), array![100.0, 110.0, 121.0]);
+    assert!(panel.row(date!(2024 - 02 - 05)).unwrap()[1].is_nan());
+    assert!(panel.row(date!(2024 - 02 - 03)).is_none());
+}
+
+#[test]
+fn to_multi_quotes() {
+    let m_quotes = PricePanel::from(&multi_quotes()).to_multi_quotes();
+    assert_eq!(m_quotes.len(), 3);
+    assert_eq!(m_quotes[0].prices["NVDA"], 50.0);
+    assert_eq!(m_quotes[2].prices.len(), 1);
+}
+
+#[test]
+fn new_shape_mismatch() {
+    let r = PricePanel::new(
+        vec![date!(2024 - 02 - 01)],
+        vec!["AAPL".to_string()],
+        array![[1.0, 2.0]],
+    );
+    assert!(matches!(r, Err(PanelError::ShapeMismatch(..))));
+}
+
+#[test]
+fn new_unsorted_dates() {
+    let new = |dates| PricePanel::new(dates, vec!["AAPL".to_string()], array![[1.0], [2.0]]);
+    assert!(matches!(
+        new(vec![date!(2024 - 02 - 02), date!(2024 - 02 - 01)]),
+        Err(PanelError::UnsortedDates(a, b))
+            if a == date!(2024 - 02 - 02) && b == date!(2024 - 02 - 01)
+    ));
+    assert!(matches!(
+        new(vec![date!(2024 - 02 - 01), date!(2024 - 02 - 01)]),
+        Err(PanelError::UnsortedDates(..))
+    ));
+    assert!(new(vec![date!(2024 - 02 - 01), date!(2024 - 02 - 02)]).is_ok());
+}
+
+#[test]
+fn from_multi_quotes_merges_duplicate_dates() {
+    let mut quotes = multi_quotes().to_vec();
+    quotes.push(MultiQuoteItem {
+        date: date!(2024 - 02 - 05),
+        prices: HashMap::from([("NVDA".to_string(), 60.0)]),
+    });
+    let panel = PricePanel::from(&Array1::from_vec(quotes));
+    assert_eq!(panel.dim(), (3, 2));
+    assert_eq!(
+        panel.row(date!(2024 - 02 - 05)).unwrap(),
+        array![121.0, 60.0]
+    );
+}
+
+#[test]
+fn select_and_between() {
+    let panel = PricePanel::from(&multi_quotes());
+    let nvda = panel.select(&["NVDA"]).unwrap();
+    assert_eq!(nvda.tickers, vec!["NVDA"]);
+    assert_eq!(nvda.dim(), (3, 1));
+    assert!(matches!(
+        panel.select(&["MSFT"]),
+        Err(PanelError::UnknownTicker(_))
+    ));
+
+    let feb = panel.between(date!(2024 - 02 - 02), date!(2024 - 02 - 04));
+    assert_eq!(feb.dates, vec![date!(2024 - 02 - 02)]);
+    assert_eq!(feb.values, array![[110.0, 55.0]]);
+    assert!(panel
+        .between(date!(2024 - 03 - 01), date!(2024 - 02 - 01))
+        .is_empty());
+}
+
+#[test]
+fn returns_and_covariance() {
+    let panel = PricePanel::from(&multi_quotes());
+    let returns = panel.returns(ReturnType::Arithmetic).unwrap();
+    assert_eq!(
+        returns.dates,
+        vec![date!(2024 - 02 - 02), date!(2024 - 02 - 05)]
+    );
+    assert_eq!(
+        returns.column("AAPL").unwrap().to_owned().rounded(4),
+        array![0.1, 0.1]
+    );
+    assert!(returns.column("NVDA").unwrap()[1].is_nan());
+
+    let cov = PricePanel::from(&multi_quotes())
+        .select(&["AAPL"])
+        .unwrap()
+        .covariance_matrix()
+        .unwrap();
+    assert_eq!(cov.dim(), (1, 1));
+    assert_eq!(cov[[0, 0]].rounded(4), 110.3333);
+}
@@ -0,0 +1,27 @@
+use super::arrow::{assert_panel_eq, panel, quotes, returns};
+use ndarray::Array1;
+use rs_quant::data::parquet::{read_parquet, write_parquet};
+use rs_quant::data::yahoo::QuoteItem;
+use time::OffsetDateTime;
+
+#[test]
+fn parquet_round_trip() {
+    let dir = std::env::temp_dir();
+
+    let path = dir.join("rs_quant_quotes.parquet");
+    write_parquet(&path, &quotes()).unwrap();
+    let r: Array1<QuoteItem> = read_parquet(&path).unwrap();
+    assert_eq!(r.len(), 3);
+    assert_eq!(r[2].datetime, quotes()[2].datetime);
+    assert_eq!(r[2].timezone, "Europe/Rome");
+    assert_eq!(r[1].close, 101.5);
+
+    let path = dir.join("rs_quant_panel.parquet");
+    write_parquet(&path, &panel()).unwrap();
+    assert_panel_eq(&read_parquet(&path).unwrap(), &panel());
+
+    let path = dir.join("rs_quant_returns.parquet");
+    write_parquet(&path, &returns()).unwrap();
+    let r: Array1<(OffsetDateTime, f64)> = read_parquet(&path).unwrap();
+    assert_eq!(r, returns());
+}
@@ -0,0 +1,124 @@
+use ndarray::Array1;
+use rs_quant::commons::date::DateTimeError;
+use rs_quant::data::polars::{column_array, DataFrameError, FromDataFrame, ToDataFrame};
+use rs_quant::data::yahoo::{MultiQuoteItem, QuoteItem};
+use rs_quant::quantitative::returns::{ReturnType, Returns};
+use rs_quant::quantitative::statistics::{MeanType, Statistic};
+use std::collections::HashMap;
+use time::macros::{date, datetime};
+use time::{Date, OffsetDateTime};
+
+fn quotes() -> Array1<QuoteItem> {
+    let quote = |datetime, close: f64| QuoteItem {
+        datetime,
+        timezone: "America/New_York".to_string(),
+        open: close,
+        high: close,
+        low: close,
+        close,
+        adjclose: close,
+        volume: 500,
+    };
+    Array1::from_vec(vec![
+        quote(datetime!(2024-02-01 9:30 -5), 100.0),
+        quote(datetime!(2024-02-02 9:30 -5), 110.0),
+        quote(datetime!(2024-02-05 9:30 -5), 99.0),
+    ])
+}
+
+fn multi_quotes() -> Array1<MultiQuoteItem> {
+    Array1::from_vec(vec![
+        MultiQuoteItem {
+            date: date!(2024 - 02 - 01),
+            prices: HashMap::from([("AAPL".to_string(), 100.0), ("NVDA".to_string(), 50.0)]),
+        },
+        MultiQuoteItem {
+            date: date!(2024 - 02 - 02),
+            prices: HashMap::from([("AAPL".to_string(), 110.0)]),
+        },
+    ])
+}
+
+#[test]
+fn quotes_dataframe() {
+    let df = quotes().to_dataframe().unwrap();
+    assert_eq!(df.shape(), (3, 7));
+    assert_eq!(
+        df.column("datetime").unwrap().dtype().to_string(),
+        "datetime[μs, America/New_York]"
+    );
+
+    let r = Array1::<QuoteItem>::from_dataframe(&df).unwrap();
+    assert_eq!(r[0].datetime, datetime!(2024-02-01 9:30 -5));
+    assert_eq!(
+        r[0].datetime.offset(),
+        datetime!(2024-02-01 9:30 -5).offset()
+    );
+    assert_eq!(r[0].timezone, "America/New_York");
+    assert_eq!(r[1].close, 110.0);
+    assert_eq!(r[2].volume, 500);
+}
+
+#[test]
+fn multi_quotes_dataframe() {
+    let df = multi_quotes().to_dataframe().unwrap();
+    assert_eq!(df.get_column_names_str(), vec!["date", "AAPL", "NVDA"]);
+    assert_eq!(df.column("NVDA").unwrap().null_count(), 1);
+
+    let r = Array1::<MultiQuoteItem>::from_dataframe(&df).unwrap();
+    assert_eq!(r[0].date, date!(2024 - 02 - 01));
+    assert_eq!(r[0].prices, multi_quotes()[0].prices);
+    assert_eq!(r[1].prices, multi_quotes()[1].prices);
+}
+
+#[test]
+fn returns_dataframe() {
+    let returns = quotes().returns(ReturnType::Arithmetic).unwrap();
+    let df = returns.to_dataframe().unwrap();
+    let r = Array1::<(OffsetDateTime, f64)>::from_dataframe(&df).unwrap();
+    assert_eq!(r, returns);
+
+    let multi = multi_quotes()
+        .returns_multiquote(ReturnType::Arithmetic)
+        .unwrap();
+    let df = multi.to_dataframe().unwrap();
+    let r = Array1::<(Date, HashMap<String, f64>)>::from_dataframe(&df).unwrap();
+    assert_eq!(r, multi);
+}
+
+#[test]
+fn dataframe_to_statistics() {
+    let df = quotes().to_dataframe().unwrap();
+    let close = column_array(&df, "close").unwrap();
+    assert_eq!(close.mean_val(MeanType::Arithmetic).unwrap(), 103.0);
+
+    let quotes = Array1::<QuoteItem>::from_dataframe(&df).unwrap();
+    assert_eq!(quotes.returns(ReturnType::Absolute).unwrap()[0].1, 1.1);
+}
+
+#[test]
+fn missing_column() {
+    let df = quotes().to_dataframe().unwrap();
+    assert!(matches!(
+        column_array(&df, "price"),
+        Err(DataFrameError::Polars(_))
+    ));
+
+    let df = multi_quotes().to_dataframe().unwrap();
+    assert!(matches!(
+        column_array(&df, "NVDA"),
+        Err(DataFrameError::NullValue(ref c)) if c == "NVDA"
+    ));
+}
+
+#[test]
+fn mixed_timezones() {
+    let mut quotes = quotes();
+    quotes[2].timezone = "Europe/Rome".to_string();
+    let r = quotes.to_dataframe();
+    assert!(
+        matches!(&r, Err(DataFrameError::DateTime(DateTimeError::MixedTimezones(a, b))) if a == "America/New_York" && b == "Europe/Rome"),
+        "error result: {:?}",
+        r
+    );
+}
@@ -0,0 +1,86 @@
+use ndarray::Array1;
+use rs_quant::commons::format::Round;
+use rs_quant::data::provider::MarketDataProvider;
+use rs_quant::data::yahoo::YahooErr;
+use rs_quant::portfolio::portfolio::Portfolio;
+use rs_quant::portfolio::position::Position;
+use rs_quant::quantitative::returns::{ReturnType, Returns};
+use time::macros::{date, datetime};
+
+use super::common::MockProvider;
+
+fn provider() -> MockProvider {
+    MockProvider::new()
+        .with_prices(
+            "AAPL",
+            &[
+                (date!(2024 - 02 - 01), 100.0),
+                (date!(2024 - 02 - 02), 105.0),
+                (date!(2024 - 02 - 05), 110.0),
+            ],
+        )
+        .with_prices(
+            "NVDA",
+            &[(date!(2024 - 02 - 01), 50.0), (date!(2024 - 02 - 02), 40.0)],
+        )
+}
+
+#[tokio::test]
+async fn update_mkt_price_with_provider() {
+    let provider = provider();
+    let mut position = Position::new("AAPL", 10.0, "USD", "2024-02-01", 100.0, 1000.0);
+    position.update_mkt_price(&provider).await.unwrap();
+    assert_eq!(position.market_price, 110.0);
+}
+
+#[tokio::test]
+async fn update_mkt_price_unknown_ticker() {
+    let provider = provider();
+    let mut position = Position::new("MSFT", 10.0, "USD", "2024-02-01", 100.0, 1000.0);
+    let r = position.update_mkt_price(&provider).await;
+    assert!(
+        matches!(r, Err(YahooErr::EmptyDataSet)),
+        "error result: {:?}",
+        r
+    );
+    assert_eq!(position.market_price, 0.0);
+}
+
+#[tokio::test]
+async fn portfolio_update_mkt_prices() {
+    let provider = provider();
+    let mut portfolio = Portfolio::new();
+    portfolio.positions = Array1::from_vec(vec![
+        Position::new("AAPL", 10.0, "USD", "2024-02-01", 100.0, 1000.0),
+        Position::new("NVDA", 25.0, "USD", "2024-02-01", 50.0, 1250.0),
+    ]);
+    portfolio.update_mkt_prices(&provider).await.unwrap();
+
+    assert_eq!(portfolio.total_mkt_val(), 2100.0);
+    assert_eq!(portfolio.total_pl(), -150.0);
+    assert_eq!(portfolio.positions[0].weight, 1100.0 / 2100.0);
+}
+
+#[tokio::test]
+async fn returns_from_provider() {
+    let provider = provider();
+    let data = provider
+        .get_quotes("AAPL", None, None, None, None)
+        .await
+        .unwrap();
+    assert_eq!(
+        data.returns(ReturnType::Arithmetic).unwrap().rounded(4),
+        ndarray::array![
+            (datetime!(2024-02-02 0:00 UTC), 0.05),
+            (datetime!(2024-02-05 0:00 UTC), 0.0476)
+        ]
+    );
+
+    let multi = provider
+        .get_multiple_quotes(vec!["AAPL", "NVDA"], None, None, None, None)
+        .await
+        .unwrap();
+    assert_eq!(multi.len(), 3);
+    assert_eq!(multi[0].prices.len(), 2);
+    assert_eq!(multi[2].prices.len(), 1);
+}
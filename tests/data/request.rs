@@ -0,0 +1,95 @@
+use rs_quant::commons::date::DateTimeError;
+use rs_quant::data::request::{Interval, QuoteRequest, Range, Span};
+use rs_quant::data::yahoo::YahooErr;
+use time::macros::datetime;
+
+#[test]
+fn parse_interval_and_range() {
+    assert_eq!("1wk".parse::<Interval>().unwrap(), Interval::OneWeek);
+    assert_eq!("ytd".parse::<Range>().unwrap(), Range::YearToDate);
+    assert_eq!(Interval::NinetyMinutes.to_string(), "90m");
+    assert!(matches!(
+        "1mo ".parse::<Interval>(),
+        Err(YahooErr::InvalidInterval(_))
+    ));
+    assert!(matches!(
+        "1w".parse::<Range>(),
+        Err(YahooErr::InvalidRange(_))
+    ));
+}
+
+#[test]
+fn build_defaults() {
+    let request = QuoteRequest::builder("AAPL").build().unwrap();
+    assert_eq!(request.interval, Interval::OneMinute);
+    assert_eq!(request.span, Span::Range(Range::OneDay));
+}
+
+#[test]
+fn build_with_dates() {
+    let request = QuoteRequest::builder("AAPL")
+        .interval(Interval::OneDay)
+        .range(Range::Max)
+        .between("2020-01-01", "2020-12-31")
+        .build_at(datetime!(2025-01-15 12:00 UTC))
+        .unwrap();
+    assert_eq!(
+        request.span,
+        Span::Dates(
+            datetime!(2020-01-01 0:00 UTC),
+            datetime!(2020-12-31 23:59:59.999_999 UTC)
+        )
+    );
+}
+
+#[test]
+fn build_invalid_combinations() {
+    let now = datetime!(2025-01-15 12:00 UTC);
+    let invalid = [
+        // intraday bars older than yahoo! finance lookback
+        QuoteRequest::builder("AAPL")
+            .interval(Interval::FiveMinutes)
+            .between("2024-01-01", "2024-01-05"),
+        // 1m request spanning more than 8 days
+        QuoteRequest::builder("AAPL").between("2025-01-01", "2025-01-14"),
+        // period too long for the interval
+        QuoteRequest::builder("AAPL").range(Range::OneMonth),
+        QuoteRequest::builder("AAPL")
+            .interval(Interval::OneHour)
+            .range(Range::Max),
+        // start after end
+        QuoteRequest::builder("AAPL")
+            .interval(Interval::OneDay)
+            .between("2024-02-01", "2024-01-01"),
+    ];
+    for builder in invalid {
+        let r = builder.build_at(now);
+        assert!(
+            matches!(r, Err(YahooErr::InvalidRequest(_))),
+            "result: {:?}",
+            r
+        );
+    }
+
+    let r = QuoteRequest::builder("AAPL")
+        .between("2024-13-01", "2024-12-31")
+        .build_at(now);
+    assert!(matches!(
+        r,
+        Err(YahooErr::DateTime(DateTimeError::InvalidDateFormat(..)))
+    ));
+}
+
+#[test]
+fn build_valid_intraday() {
+    let now = datetime!(2025-01-15 12:00 UTC);
+    assert!(QuoteRequest::builder("AAPL")
+        .between("2025-01-08", "2025-01-14")
+        .build_at(now)
+        .is_ok());
+    assert!(QuoteRequest::builder("AAPL")
+        .interval(Interval::OneHour)
+        .range(Range::OneYear)
+        .build_at(now)
+        .is_ok());
+}
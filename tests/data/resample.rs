@@ -0,0 +1,135 @@
+use ndarray::{s, Array1};
+use rs_quant::data::resample::{Frequency, Resample, ResampleError};
+use rs_quant::data::yahoo::QuoteItem;
+use time::macros::{datetime, offset, time};
+use time::{Duration, OffsetDateTime};
+
+fn bar(datetime: OffsetDateTime, price: f64) -> QuoteItem {
+    QuoteItem {
+        datetime,
+        timezone: "America/New_York".to_string(),
+        open: price,
+        high: price + 1.0,
+        low: price - 1.0,
+        close: price + 0.5,
+        adjclose: price + 0.5,
+        volume: 10,
+    }
+}
+
+// two new york sessions of 30 minute bars from 09:30 to 15:30 local time
+fn intraday() -> Array1<QuoteItem> {
+    let mut bars = Vec::new();
+    for start in [datetime!(2024-03-28 9:30 -4), datetime!(2024-04-01 9:30 -4)] {
+        for i in 0..13 {
+            bars.push(bar(start + Duration::minutes(30 * i), 100.0 + i as f64));
+        }
+    }
+    Array1::from_vec(bars)
+}
+
+#[test]
+fn parse_frequency() {
+    assert_eq!("5m".parse::<Frequency>().unwrap(), Frequency::Minutes(5));
+    assert_eq!("1h".parse::<Frequency>().unwrap(), Frequency::Hours(1));
+    assert_eq!("1wk".parse::<Frequency>().unwrap(), Frequency::Weekly);
+    assert_eq!("3mo".parse::<Frequency>().unwrap(), Frequency::QuarterEnd);
+    assert!(matches!(
+        "0m".parse::<Frequency>(),
+        Err(ResampleError::InvalidFrequency(_))
+    ));
+    assert!("2d".parse::<Frequency>().is_err());
+}
+
+#[test]
+fn hourly_session_aligned() {
+    let bars = intraday()
+        .resample_from(Frequency::Hours(1), time!(9:30))
+        .unwrap();
+    // 6 full hours plus the 15:30 bar for each session
+    assert_eq!(bars.len(), 14);
+    assert_eq!(bars[0].datetime, datetime!(2024-03-28 9:30 -4));
+    assert_eq!(bars[1].datetime, datetime!(2024-03-28 10:30 -4));
+    assert_eq!(bars[7].datetime, datetime!(2024-04-01 9:30 -4));
+
+    assert_eq!(bars[0].open, 100.0);
+    assert_eq!(bars[0].high, 102.0);
+    assert_eq!(bars[0].low, 99.0);
+    assert_eq!(bars[0].close, 101.5);
+    assert_eq!(bars[0].volume, 20);
+    assert_eq!(bars[6].volume, 10);
+}
+
+#[test]
+fn hourly_missing_open() {
+    // without the 09:30 bar the buckets stay on the session grid
+    let quotes = intraday().slice(s![1..]).to_owned();
+    let bars = quotes
+        .resample_from(Frequency::Hours(1), time!(9:30))
+        .unwrap();
+    assert_eq!(bars.len(), 14);
+    assert_eq!(bars[0].datetime, datetime!(2024-03-28 9:30 -4));
+    assert_eq!(bars[0].open, 101.0);
+    assert_eq!(bars[0].volume, 10);
+    assert_eq!(bars[1].datetime, datetime!(2024-03-28 10:30 -4));
+}
+
+#[test]
+fn hourly_clock_aligned() {
+    // from midnight the 09:30 bar starts a 09:00 bucket on its own
+    let bars = intraday().resample(Frequency::Hours(1)).unwrap();
+    assert_eq!(bars.len(), 14);
+    assert_eq!(bars[0].datetime, datetime!(2024-03-28 9:00 -4));
+    assert_eq!(bars[0].volume, 10);
+    assert_eq!(bars[1].datetime, datetime!(2024-03-28 10:00 -4));
+    assert_eq!(bars[1].volume, 20);
+    assert_eq!(bars[6].datetime, datetime!(2024-03-28 15:00 -4));
+    assert_eq!(bars[7].datetime, datetime!(2024-04-01 9:00 -4));
+}
+
+#[test]
+fn daily_label_across_dst() {
+    // new york switches to daylight saving time at 02:00 on 2024-03-10, midnight is still EST
+    let quotes = Array1::from_vec(vec![
+        bar(datetime!(2024-03-10 12:00 -4), 100.0),
+        bar(datetime!(2024-03-11 12:00 -4), 101.0),
+    ]);
+    let bars = quotes.resample(Frequency::Daily).unwrap();
+    assert_eq!(bars[0].datetime, datetime!(2024-03-10 0:00 -5));
+    assert_eq!(bars[0].datetime.offset(), offset!(-5));
+    assert_eq!(bars[1].datetime.offset(), offset!(-4));
+
+    let weekly = quotes.resample(Frequency::Weekly).unwrap();
+    assert_eq!(weekly.len(), 2);
+    assert_eq!(weekly[0].datetime.offset(), offset!(-5));
+}
+
+#[test]
+fn daily() {
+    let bars = intraday().resample(Frequency::Daily).unwrap();
+    assert_eq!(bars.len(), 2);
+    assert_eq!(bars[0].datetime, datetime!(2024-03-28 0:00 -4));
+    assert_eq!(bars[0].datetime.offset(), offset!(-4));
+    assert_eq!(bars[0].open, 100.0);
+    assert_eq!(bars[0].close, 112.5);
+    assert_eq!(bars[0].high, 113.0);
+    assert_eq!(bars[0].volume, 130);
+}
+
+#[test]
+fn weekly_and_month_end() {
+    let daily = intraday().resample(Frequency::Daily).unwrap();
+
+    // 2024-03-28 is a thursday, 2024-04-01 the following monday
+    let weekly = daily.resample(Frequency::Weekly).unwrap();
+    assert_eq!(weekly.len(), 2);
+
+    let monthly = intraday().resample(Frequency::MonthEnd).unwrap();
+    assert_eq!(monthly.len(), 2);
+    assert_eq!(monthly[0].datetime, datetime!(2024-03-28 0:00 -4));
+    assert_eq!(monthly[1].datetime, datetime!(2024-04-01 0:00 -4));
+
+    let quarterly = intraday().resample(Frequency::QuarterEnd).unwrap();
+    assert_eq!(quarterly.len(), 2);
+    assert_eq!(quarterly[1].volume, 130);
+}
@@ -0,0 +1,153 @@
+use futures::StreamExt;
+use ndarray::Array1;
+use rs_quant::data::stream::{quote_stream, StreamConfig, MIN_POLL_INTERVAL};
+use rs_quant::data::yahoo::YahooErr;
+use rs_quant::portfolio::portfolio::Portfolio;
+use rs_quant::portfolio::position::Position;
+use std::time::Duration;
+use tokio::time::Instant;
+
+use super::common::MockProvider;
+
+// provider answering latest quotes from a script, `None` fails the poll
+fn scripted(prices: &[(&str, &[Option<f64>])]) -> MockProvider {
+    prices.iter().fold(MockProvider::new(), |p, (t, script)| {
+        p.with_latest(t, script)
+    })
+}
+
+fn config(skip_unchanged: bool) -> StreamConfig {
+    StreamConfig {
+        interval: Duration::from_millis(5),
+        skip_unchanged,
+    }
+}
+
+#[tokio::test(start_paused = true)]
+async fn stream_skips_unchanged_ticks() {
+    let provider = scripted(&[("AAPL", &[Some(100.0), Some(100.0), Some(101.0)])]);
+    let ticks: Vec<f64> = quote_stream(&provider, &["AAPL"], config(true))
+        .take(2)
+        .map(|u| u.unwrap().price)
+        .collect()
+        .await;
+    assert_eq!(ticks, vec![100.0, 101.0]);
+}
+
+#[tokio::test(start_paused = true)]
+async fn stream_keeps_unchanged_ticks() {
+    let provider = scripted(&[("AAPL", &[Some(100.0), Some(100.0), Some(101.0)])]);
+    let ticks: Vec<f64> = quote_stream(&provider, &["AAPL"], config(false))
+        .take(3)
+        .map(|u| u.unwrap().price)
+        .collect()
+        .await;
+    assert_eq!(ticks, vec![100.0, 100.0, 101.0]);
+}
+
+#[tokio::test(start_paused = true)]
+async fn stream_reports_failed_ticker() {
+    let provider = scripted(&[("AAPL", &[Some(100.0)]), ("NVDA", &[None, Some(50.0)])]);
+    let updates: Vec<_> = quote_stream(&provider, &["AAPL", "NVDA"], config(true))
+        .take(3)
+        .collect()
+        .await;
+    assert_eq!(updates[0].as_ref().unwrap().symbol, "AAPL");
+    assert!(matches!(&updates[1], Err((t, YahooErr::HttpStatus(503, _))) if t == "NVDA"));
+    assert_eq!(updates[2].as_ref().unwrap().price, 50.0);
+}
+
+fn portfolio() -> Portfolio {
+    Portfolio {
+        positions: Array1::from_vec(vec![
+            Position::new("AAPL", 10.0, "USD", "2023-12-01", 100.0, 1000.0),
+            Position::new("NVDA", 20.0, "USD", "2023-12-01", 50.0, 1000.0),
+        ]),
+    }
+}
+
+#[tokio::test(start_paused = true)]
+async fn portfolio_subscribe() {
+    let provider = scripted(&[
+        ("AAPL", &[Some(110.0), Some(120.0)]),
+        ("NVDA", &[None, Some(40.0)]),
+    ]);
+    let mut p = portfolio();
+    let mut updates = 0;
+    p.subscribe(&provider, Duration::from_millis(5), |_| {
+        updates += 1;
+        updates < 3
+    })
+    .await
+    .unwrap();
+
+    assert_eq!(updates, 3);
+    assert_eq!(p.positions[0].market_price, 120.0);
+    assert_eq!(p.positions[0].market_value, 1200.0);
+    assert_eq!(p.positions[0].pl, 200.0);
+    assert_eq!(p.positions[1].market_price, 40.0);
+    assert_eq!(p.positions[1].pl, -200.0);
+    assert_eq!(p.positions[0].weight, 0.6);
+}
+
+#[tokio::test(start_paused = true)]
+async fn portfolio_subscribe_fails_on_permanent_error() {
+    let provider = scripted(&[("AAPL", &[Some(110.0)])]);
+    let mut p = portfolio();
+    let r = p
+        .subscribe(&provider, Duration::from_millis(5), |_| true)
+        .await;
+    assert!(
+        matches!(r, Err(YahooErr::EmptyDataSet)),
+        "error result: {:?}",
+        r
+    );
+    assert_eq!(p.positions[0].market_price, 110.0);
+}
+
+#[tokio::test(start_paused = true)]
+async fn portfolio_subscribe_calls_back_every_cycle() {
+    // prices never move and NVDA polls always fail transiently, the caller can still stop
+    let provider = scripted(&[("AAPL", &[Some(100.0)]), ("NVDA", &[None])]);
+    let mut p = portfolio();
+    let mut cycles = 0;
+    p.subscribe(&provider, Duration::from_millis(5), |_| {
+        cycles += 1;
+        cycles < 3
+    })
+    .await
+    .unwrap();
+
+    assert_eq!(cycles, 3);
+    assert_eq!(p.positions[0].market_price, 100.0);
+}
+
+#[tokio::test(start_paused = true)]
+async fn zero_interval_is_clamped() {
+    let provider = scripted(&[("AAPL", &[Some(100.0), Some(101.0)])]);
+    let config = StreamConfig {
+        interval: Duration::ZERO,
+        skip_unchanged: false,
+    };
+    let start = Instant::now();
+    let ticks: Vec<f64> = quote_stream(&provider, &["AAPL"], config)
+        .take(2)
+        .map(|u| u.unwrap().price)
+        .collect()
+        .await;
+    assert_eq!(ticks, vec![100.0, 101.0]);
+    assert!(start.elapsed() >= MIN_POLL_INTERVAL);
+
+    let start = Instant::now();
+    let mut p = portfolio();
+    let provider = scripted(&[("AAPL", &[Some(110.0)]), ("NVDA", &[Some(40.0)])]);
+    let mut cycles = 0;
+    p.subscribe(&provider, Duration::ZERO, |_| {
+        cycles += 1;
+        cycles < 2
+    })
+    .await
+    .unwrap();
+    assert_eq!(cycles, 2);
+    assert!(start.elapsed() >= MIN_POLL_INTERVAL);
+}
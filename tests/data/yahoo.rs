@@ -1,123 +1,610 @@
-use rs_quant::data::yahoo::{OptionType, Yahoo, YahooErr};
+use rs_quant::data::batch::{get_quotes_with_retry, BatchConfig};
+use rs_quant::data::provider::MarketDataProvider;
+use rs_quant::data::yahoo::{OptionType, YahooErr};
+use std::time::Duration;
+use time::macros::{date, datetime};
+use wiremock::matchers::{header, method, path, query_param, query_param_is_missing};
+use wiremock::{Mock, ResponseTemplate};
+
+use super::common::yahoo::{mock_server, mount, provider};
 
 #[tokio::test]
 async fn fetch_failed() {
-    let conn = Yahoo::provider().unwrap();
-    let r = conn
+    let server = mock_server().await;
+    mount(
+        &server,
+        "/v8/finance/chart/INVALID_SYMBOL",
+        404,
+        include_str!("../fixtures/yahoo/chart_not_found.json"),
+    )
+    .await;
+    let r = provider(&server)
         .get_quotes("INVALID_SYMBOL", None, None, None, None)
         .await;
     assert!(
-        matches!(r, Err(YahooErr::FetchFailed(_))),
+        matches!(r, Err(YahooErr::HttpStatus(404, _))),
+        "error result: {:?}",
+        r
+    )
+}
+
+#[tokio::test]
+async fn fetch_failed_without_json_body() {
+    let server = mock_server().await;
+    mount(
+        &server,
+        "/v8/finance/chart/AAPL",
+        500,
+        "Internal Server Error",
+    )
+    .await;
+    let r = provider(&server)
+        .get_quotes("AAPL", None, None, None, None)
+        .await;
+    assert!(
+        matches!(r, Err(YahooErr::HttpStatus(500, _))),
+        "error result: {:?}",
+        r
+    )
+}
+
+#[tokio::test]
+async fn not_found_is_not_retried() {
+    let server = mock_server().await;
+    Mock::given(method("GET"))
+        .and(path("/v8/finance/chart/INVALID_SYMBOL"))
+        .respond_with(
+            ResponseTemplate::new(404)
+                .set_body_string(include_str!("../fixtures/yahoo/chart_not_found.json")),
+        )
+        .expect(1)
+        .mount(&server)
+        .await;
+    let yahoo = provider(&server);
+    let r = get_quotes_with_retry(
+        &yahoo,
+        "INVALID_SYMBOL",
+        None,
+        None,
+        None,
+        None,
+        &BatchConfig::default(),
+    )
+    .await;
+    assert!(
+        matches!(&r, Err(e) if !yahoo.is_transient(e)),
+        "error result: {:?}",
+        r
+    );
+    server.verify().await;
+}
+
+#[tokio::test]
+async fn server_error_is_retried() {
+    let server = mock_server().await;
+    Mock::given(method("GET"))
+        .and(path("/v8/finance/chart/AAPL"))
+        .respond_with(ResponseTemplate::new(503).set_body_string("Service Unavailable"))
+        .expect(3)
+        .mount(&server)
+        .await;
+    let config = BatchConfig {
+        max_retries: 2,
+        backoff: Duration::from_millis(1),
+        ..BatchConfig::default()
+    };
+    let r =
+        get_quotes_with_retry(&provider(&server), "AAPL", None, None, None, None, &config).await;
+    assert!(
+        matches!(r, Err(YahooErr::HttpStatus(503, _))),
+        "error result: {:?}",
+        r
+    );
+    server.verify().await;
+}
+
+#[tokio::test]
+async fn invalid_interval() {
+    let server = mock_server().await;
+    let r = provider(&server)
+        .get_quotes("AAPL", None, None, None, Some("1mo "))
+        .await;
+    assert!(
+        matches!(r, Err(YahooErr::InvalidInterval(_))),
+        "error result: {:?}",
+        r
+    )
+}
+
+#[tokio::test]
+async fn invalid_json() {
+    let server = mock_server().await;
+    mount(&server, "/v8/finance/chart/AAPL", 200, "{\"chart\": [").await;
+    let r = provider(&server)
+        .get_quotes("AAPL", None, None, Some("5d"), Some("1d"))
+        .await;
+    assert!(
+        matches!(r, Err(YahooErr::DeserializationFailed(_))),
         "error result: {:?}",
         r
     )
 }
 
-// #[tokio::test] // failing because query returns fetch failed error
-// async fn invalid_json() {
-//     let conn = Yahoo::provider().unwrap();
-//     let r = conn
-//         .get_quotes("AAPL", None, None, None, Some("invalid-interval"))
-//         .await;
-//     assert!(
-//         matches!(r, Err(YahooErr::InvalidJson)),
-//         "error result: {:?}",
-//         r
-//     )
-// }
-
-// #[tokio::test] // failing, prints correct dataset with correct dates. maybe checks already made convert to default case
-// async fn invalid_dateformat() {
-//     let conn = Yahoo::provider().unwrap();
-//     let r = conn
-//         .get_quotes("AAPL", Some("invalid-date"), None, None, None)
-//         .await;
-//     assert!(
-//         matches!(r, Err(YahooErr::InvalidDateFormat(_))),
-//         "error result: {:?}",
-//         r
-//     )
-// }
-
-// #[tokio::test] // failing because query returns fetch failed error
-// async fn empty_dataset() {
-//     let conn = Yahoo::provider().unwrap();
-//     let r = conn
-//         .get_quotes("empty_simbol", None, None, None, None)
-//         .await;
-//     assert!(
-//         matches!(r, Err(YahooErr::EmptyDataSet)),
-//         "error result: {:?}",
-//         r
-//     )
-// }
+#[tokio::test]
+async fn empty_dataset() {
+    let server = mock_server().await;
+    mount(
+        &server,
+        "/v8/finance/chart/AAPL",
+        200,
+        include_str!("../fixtures/yahoo/chart_empty.json"),
+    )
+    .await;
+    let r = provider(&server)
+        .get_quotes("AAPL", None, None, Some("5d"), Some("1d"))
+        .await;
+    assert!(
+        matches!(r, Err(YahooErr::EmptyDataSet)),
+        "error result: {:?}",
+        r
+    )
+}
 
 #[tokio::test]
 async fn get_quotes_without_args() {
-    let conn = Yahoo::provider().unwrap();
-    let res = conn.get_quotes("VUAA.MI", None, None, None, None).await;
+    let server = mock_server().await;
+    Mock::given(method("GET"))
+        .and(path("/v8/finance/chart/AAPL"))
+        .and(query_param("interval", "1m"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_string(include_str!("../fixtures/yahoo/chart_daily.json")),
+        )
+        .mount(&server)
+        .await;
+    let res = provider(&server)
+        .get_quotes("AAPL", None, None, None, None)
+        .await;
     assert!(res.is_ok(), "Error: {:?}", res);
     assert!(!res.unwrap().is_empty())
 }
 
 #[tokio::test]
 async fn get_quotes_with_date_range() {
-    let conn = Yahoo::provider().unwrap();
-    let res = conn
+    let server = mock_server().await;
+    Mock::given(method("GET"))
+        .and(path("/v8/finance/chart/AAPL"))
+        .and(query_param("interval", "1d"))
+        .and(query_param_is_missing("range"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_string(include_str!("../fixtures/yahoo/chart_daily.json")),
+        )
+        .mount(&server)
+        .await;
+    let res = provider(&server)
         .get_quotes(
-            "VUAA.MI",
-            Some("2024-01-01"),
-            Some("2025-01-01"),
+            "AAPL",
+            Some("2024-02-01"),
+            Some("2024-02-06"),
             None,
             Some("1d"),
         )
         .await;
     assert!(res.is_ok(), "Error: {:?}", res);
-    assert!(!res.unwrap().is_empty());
+    assert_eq!(res.unwrap().len(), 3);
+
+    let requests = server.received_requests().await.unwrap();
+    let url = &requests.last().unwrap().url;
+    assert!(url.query_pairs().any(|(k, _)| k == "period1"));
+    assert!(url.query_pairs().any(|(k, _)| k == "period2"));
 }
 
 #[tokio::test]
 async fn get_quotes_with_period() {
-    let conn = Yahoo::provider().unwrap();
-    let res = conn
-        .get_quotes("AAPL", None, None, Some("5d"), Some("1d"))
+    let server = mock_server().await;
+    Mock::given(method("GET"))
+        .and(path("/v8/finance/chart/AAPL"))
+        .and(query_param("interval", "1d"))
+        .and(query_param("range", "5d"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_string(include_str!("../fixtures/yahoo/chart_daily.json")),
+        )
+        .mount(&server)
         .await;
-    assert!(res.is_ok(), "Error: {:?}", res);
-    assert!(!res.unwrap().is_empty())
+    let quotes = provider(&server)
+        .get_quotes("AAPL", None, None, Some("5d"), Some("1d"))
+        .await
+        .unwrap();
+
+    // bar with null prices is skipped
+    assert_eq!(quotes.len(), 3);
+    assert_eq!(quotes[0].datetime, datetime!(2024-02-01 9:30 -5));
+    assert_eq!(quotes[0].timezone, "America/New_York");
+    assert_eq!(quotes[0].open, 183.99);
+    assert_eq!(quotes[0].close, 186.86);
+    assert_eq!(quotes[0].adjclose, 185.94);
+    assert_eq!(quotes[0].volume, 64885400);
+    assert_eq!(quotes[2].datetime, datetime!(2024-02-06 9:30 -5));
 }
 
 #[tokio::test]
 async fn get_multiple_quotes() {
-    let tickers = vec!["NVDA", "SWDA.MI", "VUAA.L", "IBGX.AS"];
-    let conn = Yahoo::provider().unwrap();
-    let res = conn
-        .get_multiple_quotes(tickers, None, None, Some("5d"), Some("1d"))
+    let server = mock_server().await;
+    for ticker in ["AAPL", "MSFT"] {
+        mount(
+            &server,
+            &format!("/v8/finance/chart/{}", ticker),
+            200,
+            include_str!("../fixtures/yahoo/chart_daily.json"),
+        )
+        .await;
+    }
+    let res = provider(&server)
+        .get_multiple_quotes(vec!["AAPL", "MSFT"], None, None, Some("5d"), Some("1d"))
         .await;
     assert!(res.is_ok(), "Error: {:?}", res);
-    assert!(!res.unwrap().is_empty())
+    let res = res.unwrap();
+    assert_eq!(res.len(), 3);
+    assert_eq!(res[0].date, date!(2024 - 02 - 01));
+    assert_eq!(res[0].prices["MSFT"], 185.94);
+    assert!(res.iter().all(|q| q.prices.len() == 2))
+}
+
+#[tokio::test]
+async fn get_multiple_quotes_with_config() {
+    let server = mock_server().await;
+    mount(
+        &server,
+        "/v8/finance/chart/AAPL",
+        200,
+        include_str!("../fixtures/yahoo/chart_daily.json"),
+    )
+    .await;
+    Mock::given(method("GET"))
+        .and(path("/v8/finance/chart/MSFT"))
+        .respond_with(ResponseTemplate::new(503).set_body_string("Service Unavailable"))
+        .expect(2)
+        .mount(&server)
+        .await;
+    let config = BatchConfig {
+        concurrency: 1,
+        max_retries: 1,
+        backoff: Duration::from_millis(1),
+    };
+    let res = provider(&server)
+        .get_multiple_quotes_with(
+            vec!["AAPL", "MSFT"],
+            None,
+            None,
+            Some("5d"),
+            Some("1d"),
+            &config,
+        )
+        .await;
+    assert!(
+        matches!(res, Err(YahooErr::HttpStatus(503, _))),
+        "error result: {:?}",
+        res
+    );
+    server.verify().await;
+}
+
+#[tokio::test]
+async fn get_multiple_quotes_fails_on_invalid_ticker() {
+    let server = mock_server().await;
+    mount(
+        &server,
+        "/v8/finance/chart/AAPL",
+        200,
+        include_str!("../fixtures/yahoo/chart_daily.json"),
+    )
+    .await;
+    // unknown symbols are a permanent failure, the default retry policy must not retry them
+    Mock::given(method("GET"))
+        .and(path("/v8/finance/chart/INVALID_SYMBOL"))
+        .respond_with(
+            ResponseTemplate::new(404)
+                .set_body_string(include_str!("../fixtures/yahoo/chart_not_found.json")),
+        )
+        .expect(1)
+        .mount(&server)
+        .await;
+    let batch = provider(&server)
+        .get_multiple_quotes_batch(
+            vec!["AAPL", "INVALID_SYMBOL"],
+            None,
+            None,
+            Some("5d"),
+            Some("1d"),
+            &BatchConfig::default(),
+        )
+        .await;
+    assert_eq!(batch.quotes.len(), 1);
+    assert_eq!(batch.failed_tickers(), vec!["INVALID_SYMBOL"]);
+    server.verify().await;
 }
 
 #[tokio::test]
 async fn get_options() {
-    let conn = Yahoo::provider().unwrap();
+    let server = mock_server().await;
+    Mock::given(method("GET"))
+        .and(path("/v7/finance/options/AAPL"))
+        .and(query_param("crumb", "test-crumb"))
+        .and(header("cookie", "A3=d=test"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_string(include_str!("../fixtures/yahoo/options.json")),
+        )
+        .mount(&server)
+        .await;
+    let conn = provider(&server);
+
     let call = conn.get_options("AAPL", OptionType::Call).await;
     assert!(call.is_ok(), "Error: {:?}", call);
-    assert!(!call.unwrap().is_empty());
+    let call = call.unwrap();
+    assert_eq!(call.len(), 2);
+    assert_eq!(
+        call[0].contract_symbol.as_deref(),
+        Some("AAPL240621C00180000")
+    );
+    assert_eq!(call[0].in_the_money, Some(true));
+    assert_eq!(call[1].change, None);
 
     let put = conn.get_options("AAPL", OptionType::Put).await;
     assert!(put.is_ok(), "Error: {:?}", put);
-    assert!(!put.unwrap().is_empty())
+    let put = put.unwrap();
+    assert_eq!(put.len(), 1);
+    assert_eq!(put[0].strike, Some(180.0))
+}
+
+#[tokio::test]
+async fn get_options_empty_dataset() {
+    let server = mock_server().await;
+    mount(
+        &server,
+        "/v7/finance/options/AAPL",
+        200,
+        "{\"optionChain\": {\"result\": [], \"error\": null}}",
+    )
+    .await;
+    let r = provider(&server)
+        .get_options("AAPL", OptionType::Call)
+        .await;
+    assert!(
+        matches!(r, Err(YahooErr::EmptyDataSet)),
+        "error result: {:?}",
+        r
+    )
+}
+
+#[tokio::test]
+async fn get_option_chain() {
+    let server = mock_server().await;
+    Mock::given(method("GET"))
+        .and(path("/v7/finance/options/AAPL"))
+        .and(query_param_is_missing("date"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_string(include_str!("../fixtures/yahoo/options.json")),
+        )
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/v7/finance/options/AAPL"))
+        .and(query_param("date", "1719532800"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_string(include_str!("../fixtures/yahoo/options_1719532800.json")),
+        )
+        .mount(&server)
+        .await;
+    let chain = provider(&server).get_option_chain("AAPL").await;
+    assert!(chain.is_ok(), "Error: {:?}", chain);
+    let chain = chain.unwrap();
+    assert_eq!(
+        chain.expirations(),
+        vec![date!(2024 - 06 - 21), date!(2024 - 06 - 28)]
+    );
+    assert_eq!(chain.underlying_price, Some(185.0));
+    assert_eq!(chain.currency.as_deref(), Some("USD"));
+    assert_eq!(chain.contracts(OptionType::Call).len(), 3);
+    assert_eq!(chain.contracts(OptionType::Put).len(), 1);
 }
 
 #[tokio::test]
 async fn get_latest_quote() {
-    let conn = Yahoo::provider().unwrap();
-    let quote = conn.get_latest_quote("NVDA").await;
+    let server = mock_server().await;
+    Mock::given(method("GET"))
+        .and(path("/v8/finance/chart/NVDA"))
+        .and(query_param("interval", "1d"))
+        .and(query_param("range", "1mo"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_string(include_str!("../fixtures/yahoo/chart_daily.json")),
+        )
+        .mount(&server)
+        .await;
+    let quote = provider(&server).get_latest_quote("NVDA").await;
     assert!(quote.is_ok(), "Error: {:?}", quote);
+    assert_eq!(quote.unwrap(), 189.3);
+}
+
+#[tokio::test]
+async fn search() {
+    let server = mock_server().await;
+    Mock::given(method("GET"))
+        .and(path("/v1/finance/search"))
+        .and(query_param("q", "apple"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_string(include_str!("../fixtures/yahoo/search.json")),
+        )
+        .mount(&server)
+        .await;
+    let res = provider(&server).search("apple").await;
+    assert!(res.is_ok(), "Error: {:?}", res);
+    let res = res.unwrap();
+    assert_eq!(res.len(), 2);
+    assert_eq!(res[0].symbol, "AAPL");
+    assert_eq!(res[0].long_name.as_deref(), Some("Apple Inc."));
+    assert_eq!(res[0].quote_type.as_deref(), Some("EQUITY"));
+    assert_eq!(res[1].long_name, None);
 }
 
 #[tokio::test]
+async fn search_invalid_json() {
+    let server = mock_server().await;
+    mount(&server, "/v1/finance/search", 200, "<html></html>").await;
+    let r = provider(&server).search("apple").await;
+    assert!(
+        matches!(r, Err(YahooErr::DeserializationFailed(_))),
+        "error result: {:?}",
+        r
+    )
+}
+
+#[tokio::test]
+#[allow(deprecated)]
 async fn search_asset() {
-    let conn = Yahoo::provider().unwrap();
-    assert!(conn.search_asset("AAPL").await.is_ok());
+    let server = mock_server().await;
+    Mock::given(method("GET"))
+        .and(path("/v1/finance/search"))
+        .and(query_param("q", "apple"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_string(include_str!("../fixtures/yahoo/search.json")),
+        )
+        .mount(&server)
+        .await;
+    let res = provider(&server).search_asset("apple").await;
+    assert!(res.is_ok(), "Error: {:?}", res);
+    let res = res.unwrap();
+    assert_eq!(res.quotes.len(), 2);
+    assert_eq!(res.quotes[0].symbol, "AAPL");
+    assert_eq!(res.quotes[1].symbol, "AAPL.NE");
+}
+
+#[tokio::test]
+#[allow(deprecated)]
+async fn search_asset_empty_results() {
+    let server = mock_server().await;
+    mount(
+        &server,
+        "/v1/finance/search",
+        200,
+        include_str!("../fixtures/yahoo/search_empty.json"),
+    )
+    .await;
+    let res = provider(&server).search_asset("no such asset").await;
+    assert!(res.is_ok(), "Error: {:?}", res);
+    assert!(res.unwrap().quotes.is_empty());
+}
+
+#[tokio::test]
+#[allow(deprecated)]
+async fn search_asset_invalid_json() {
+    let server = mock_server().await;
+    mount(&server, "/v1/finance/search", 200, "<html></html>").await;
+    let r = provider(&server).search_asset("apple").await;
+    assert!(
+        matches!(r, Err(YahooErr::DeserializationFailed(_))),
+        "error result: {:?}",
+        r
+    )
+}
+
+#[tokio::test]
+async fn get_dividends() {
+    let server = mock_server().await;
+    Mock::given(method("GET"))
+        .and(path("/v8/finance/chart/AAPL"))
+        .and(query_param("events", "div|split"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_string(include_str!("../fixtures/yahoo/chart_events.json")),
+        )
+        .mount(&server)
+        .await;
+    let dividends = provider(&server)
+        .get_dividends("AAPL", "2020-01-01", "2025-01-01")
+        .await;
+    assert!(dividends.is_ok(), "Error: {:?}", dividends);
+    let dividends = dividends.unwrap();
+    assert_eq!(dividends.len(), 1);
+    assert_eq!(dividends[0].date, date!(2024 - 02 - 09));
+    assert_eq!(dividends[0].amount, 0.24);
+}
+
+#[tokio::test]
+async fn get_splits() {
+    let server = mock_server().await;
+    mount(
+        &server,
+        "/v8/finance/chart/AAPL",
+        200,
+        include_str!("../fixtures/yahoo/chart_events.json"),
+    )
+    .await;
+    let splits = provider(&server)
+        .get_splits("AAPL", "2020-01-01", "2025-01-01")
+        .await;
+    assert!(splits.is_ok(), "Error: {:?}", splits);
+    let splits = splits.unwrap();
+    assert_eq!(splits.len(), 1);
+    assert_eq!(splits[0].date, date!(2020 - 08 - 31));
+    assert_eq!(splits[0].ratio(), 4.0);
+}
+
+#[tokio::test]
+async fn get_quote_summary() {
+    let server = mock_server().await;
+    Mock::given(method("GET"))
+        .and(path("/v10/finance/quoteSummary/AAPL"))
+        .and(query_param("crumb", "test-crumb"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_string(include_str!("../fixtures/yahoo/quote_summary.json")),
+        )
+        .mount(&server)
+        .await;
+    let summary = provider(&server).get_quote_summary("AAPL").await;
+    assert!(summary.is_ok(), "Error: {:?}", summary);
+    let summary = summary.unwrap();
+    assert_eq!(summary.symbol, "AAPL");
+    assert_eq!(summary.sector.as_deref(), Some("Technology"));
+    assert_eq!(summary.market_cap, Some(2923000000000.0));
+    assert_eq!(summary.trailing_pe, Some(29.45));
+}
+
+#[tokio::test]
+async fn get_quote_summaries() {
+    let server = mock_server().await;
+    for ticker in ["AAPL", "MSFT"] {
+        mount(
+            &server,
+            &format!("/v10/finance/quoteSummary/{}", ticker),
+            200,
+            include_str!("../fixtures/yahoo/quote_summary.json"),
+        )
+        .await;
+    }
+    Mock::given(method("GET"))
+        .and(path("/v10/finance/quoteSummary/INVALID_SYMBOL"))
+        .respond_with(ResponseTemplate::new(404).set_body_string(
+            "{\"quoteSummary\": {\"result\": null, \"error\": {\"code\": \"Not Found\"}}}",
+        ))
+        .expect(1)
+        .mount(&server)
+        .await;
+    let batch = provider(&server)
+        .get_quote_summaries(&["AAPL", "INVALID_SYMBOL", "MSFT"], &BatchConfig::default())
+        .await;
+    assert!(batch.is_ok(), "Error: {:?}", batch);
+    let batch = batch.unwrap();
+    assert_eq!(batch.summaries.len(), 2);
+    assert_eq!(batch.errors[0].0, "INVALID_SYMBOL");
+    assert!(matches!(batch.errors[0].1, YahooErr::HttpStatus(404, _)));
+    server.verify().await;
 }
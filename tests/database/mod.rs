@@ -1,2 +1,26 @@
 pub mod connection;
 pub mod queries;
+
+#[cfg(feature = "sqlite")]
+use rs_quant::database::connection::DbConnection;
+
+// fresh sqlite database file in the temp dir, one per test so they can run in parallel
+#[cfg(feature = "sqlite")]
+pub async fn sqlite(name: &str) -> DbConnection {
+    let path = std::env::temp_dir().join(format!("rs_quant_{name}.db"));
+    let _ = std::fs::remove_file(&path);
+    DbConnection::connect(&format!("sqlite://{}", path.display()))
+        .await
+        .unwrap()
+}
+
+// run raw statements on a sqlite connection
+#[cfg(feature = "sqlite")]
+pub async fn execute(db: &DbConnection, statements: &[&str]) {
+    let rs_quant::database::connection::DbPool::Sqlite(p) = &db.pool else {
+        panic!("expected sqlite pool")
+    };
+    for s in statements {
+        sqlx::query(s).execute(p).await.unwrap();
+    }
+}
@@ -1,5 +1,7 @@
 use rs_quant::database::connection::DbConnection;
-use rs_quant::database::queries::{portfolio_table, portfolio_tickers};
+use rs_quant::database::queries::{
+    portfolio_table, portfolio_tickers, DbQueryError, TransactionKind,
+};
 
 #[tokio::test]
 async fn test_portfolio_tickers() {
@@ -14,3 +16,592 @@ async fn test_portfolio_table() {
     let t = portfolio_table(&pool.pool).await;
     assert!(t.is_ok());
 }
+
+#[test]
+fn transaction_kind() {
+    assert_eq!(
+        "Withdrawal".parse::<TransactionKind>().unwrap(),
+        TransactionKind::Withdrawal
+    );
+    assert_eq!(TransactionKind::Split.to_string(), "split");
+    assert!(matches!(
+        "transfer".parse::<TransactionKind>(),
+        Err(DbQueryError::UnknownTransactionKind(_))
+    ));
+}
+
+#[cfg(feature = "sqlite")]
+mod sqlite {
+    use chrono::NaiveDate;
+    use ndarray::Array1;
+    use rs_quant::commons::date::weekends;
+    use rs_quant::data::yahoo::QuoteItem;
+    use rs_quant::database::connection::DbPool;
+    use rs_quant::database::queries::{
+        delete_transaction, get_instrument, insert_transaction, list_transactions, load_quotes,
+        missing_ranges, portfolio_table, portfolio_tickers, upsert_instrument, upsert_quotes,
+        DbQueryError, Instrument, Transaction, TransactionKind,
+    };
+    use rs_quant::portfolio::portfolio::Portfolio;
+    use sqlx::types::Decimal;
+    use std::str::FromStr;
+    use time::macros::{date, datetime};
+    use time::OffsetDateTime;
+    use time_tz::{timezones, OffsetDateTimeExt};
+
+    #[tokio::test]
+    async fn sqlite_portfolio_table() {
+        let db = super::super::sqlite("portfolio_table").await;
+        db.migrate().await.unwrap();
+        super::super::execute(
+            &db,
+            &[
+                "INSERT INTO instruments (ticker, currency) VALUES ('VUAA.MI', 'EUR'), ('AAPL', 'USD')",
+                "INSERT INTO positions VALUES ('VUAA.MI', '10', '2024-01-02', '100.5'), ('AAPL', '2', '2024-03-01', '150')",
+                "INSERT INTO prices (ticker, ts, bar_interval, open, high, low, close, adjclose) VALUES ('VUAA.MI', 1717372800, '1d', 1, 1, 1, 110.25, 110.25), ('AAPL', 1717372800, '1d', 1, 1, 1, 180, 180)",
+            ],
+        )
+        .await;
+
+        let mut tickers = portfolio_tickers(&db.pool).await.unwrap();
+        tickers.sort();
+        assert_eq!(tickers, vec!["AAPL", "VUAA.MI"]);
+
+        let table = portfolio_table(&db.pool).await.unwrap();
+        assert_eq!(table.len(), 2);
+        let vuaa = table.iter().find(|r| r.ticker == "VUAA.MI").unwrap();
+        assert_eq!(vuaa.quantity, Decimal::from(10));
+        assert_eq!(vuaa.buy_date.to_string(), "2024-01-02");
+        assert_eq!(vuaa.buy_price, Decimal::new(1005, 1));
+        assert_eq!(vuaa.pl, Decimal::new(975, 1));
+        assert_eq!(vuaa.pl_pct.round_dp(3), Decimal::new(97, 3));
+        let aapl = table.iter().find(|r| r.ticker == "AAPL").unwrap();
+        assert_eq!(aapl.market_value, Decimal::from(360));
+    }
+
+    #[tokio::test]
+    async fn sqlite_empty_portfolio() {
+        let db = super::super::sqlite("empty_portfolio").await;
+        db.migrate().await.unwrap();
+        assert!(matches!(
+            portfolio_tickers(&db.pool).await,
+            Err(DbQueryError::Notfound)
+        ));
+        assert!(portfolio_table(&db.pool).await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn sqlite_zero_buy_price() {
+        let db = super::super::sqlite("zero_buy_price").await;
+        db.migrate().await.unwrap();
+        super::super::execute(
+            &db,
+            &[
+                "INSERT INTO instruments (ticker, currency) VALUES ('GIFT', 'USD')",
+                "INSERT INTO positions VALUES ('GIFT', '5', '2024-01-02', '0.00')",
+                "INSERT INTO prices (ticker, ts, bar_interval, open, high, low, close, adjclose) VALUES ('GIFT', 1717372800, '1d', 1, 1, 1, 12, 12)",
+            ],
+        )
+        .await;
+
+        // the view itself returns a zero return, not NULL, for a text zero
+        let DbPool::Sqlite(p) = &db.pool else {
+            panic!("expected sqlite pool")
+        };
+        let pl_pct: Option<i64> =
+            sqlx::query_scalar("SELECT PL_pct FROM vw_portfolio WHERE ticker = 'GIFT'")
+                .fetch_one(p)
+                .await
+                .unwrap();
+        assert_eq!(pl_pct, Some(0));
+
+        let row = &portfolio_table(&db.pool).await.unwrap()[0];
+        assert_eq!(row.buy_value, Decimal::ZERO);
+        assert_eq!(row.market_value, Decimal::from(60));
+        assert_eq!(row.pl, Decimal::from(60));
+        assert_eq!(row.pl_pct, Decimal::ZERO);
+    }
+
+    #[tokio::test]
+    async fn sqlite_invalid_position_value() {
+        let db = super::super::sqlite("invalid_position_value").await;
+        db.migrate().await.unwrap();
+        super::super::execute(
+            &db,
+            &[
+                "INSERT INTO instruments (ticker, currency) VALUES ('AAPL', 'USD'), ('VUAA.MI', 'EUR')",
+                "INSERT INTO positions VALUES ('AAPL', '2', '2024-03-01', '150'), ('VUAA.MI', 'ten', '2024-01-02', '100.5')",
+            ],
+        )
+        .await;
+
+        match portfolio_table(&db.pool).await {
+            Err(DbQueryError::InvalidPosition { ticker, field, .. }) => {
+                assert_eq!(ticker, "VUAA.MI");
+                assert_eq!(field, "quantity");
+            }
+            r => panic!("unexpected result: {r:?}"),
+        }
+    }
+
+    #[tokio::test]
+    async fn sqlite_missing_ranges_current_session() {
+        let db = super::super::sqlite("missing_ranges_today").await;
+        db.migrate().await.unwrap();
+        upsert_instrument(&db.pool, &Instrument::new("BTC-USD", "USD"))
+            .await
+            .unwrap();
+
+        // a bar stored mid-session does not cover today, the final close is fetched again
+        let now = OffsetDateTime::now_utc();
+        let yesterday = now - time::Duration::days(1);
+        upsert_quotes(
+            &db.pool,
+            "BTC-USD",
+            "1d",
+            &Array1::from_vec(vec![bar(yesterday, 100.0), bar(now, 101.0)]),
+        )
+        .await
+        .unwrap();
+        let (start, today) = (yesterday.date(), now.date());
+        let missing = missing_ranges(&db.pool, "BTC-USD", "1d", start, today, &[])
+            .await
+            .unwrap();
+        assert_eq!(missing, vec![(today, today)]);
+    }
+
+    #[tokio::test]
+    async fn sqlite_migrated_portfolio_view() {
+        let db = super::super::sqlite("migrated_view").await;
+        db.migrate().await.unwrap();
+        super::super::execute(
+            &db,
+            &[
+                "INSERT INTO instruments (ticker, currency) VALUES ('AAPL', 'USD'), ('VUAA.MI', 'EUR')",
+                "INSERT INTO positions VALUES ('AAPL', 10, '2024-01-02', 150), ('VUAA.MI', 2.5, '2024-03-01', 100)",
+                // latest daily close is used, intraday bars are ignored
+                "INSERT INTO prices (ticker, ts, bar_interval, open, high, low, close, adjclose) VALUES ('AAPL', 1717372800, '1d', 1, 1, 1, 170, 170), ('AAPL', 1717459200, '1d', 1, 1, 1, 180, 180), ('AAPL', 1717500000, '1h', 1, 1, 1, 190, 190)",
+            ],
+        )
+        .await;
+
+        let table = portfolio_table(&db.pool).await.unwrap();
+        let aapl = table.iter().find(|r| r.ticker == "AAPL").unwrap();
+        assert_eq!(aapl.currency, "USD");
+        assert_eq!(aapl.buy_value, Decimal::from(1500));
+        assert_eq!(aapl.market_price, Decimal::from(180));
+        assert_eq!(aapl.market_value, Decimal::from(1800));
+        assert_eq!(aapl.pl, Decimal::from(300));
+        assert_eq!(aapl.pl_pct, Decimal::new(2, 1));
+
+        // no stored price: valued at buy price
+        let vuaa = table.iter().find(|r| r.ticker == "VUAA.MI").unwrap();
+        assert_eq!(vuaa.quantity, Decimal::new(25, 1));
+        assert_eq!(vuaa.market_value, Decimal::from(250));
+        assert_eq!(vuaa.pl_pct, Decimal::ZERO);
+    }
+
+    #[tokio::test]
+    async fn sqlite_positions_decimal_text() {
+        let db = super::super::sqlite("positions_decimal_text").await;
+        db.migrate().await.unwrap();
+        super::super::execute(
+            &db,
+            &[
+                "INSERT INTO instruments (ticker, currency) VALUES ('BTC-USD', 'USD')",
+                "INSERT INTO positions VALUES ('BTC-USD', '0.123456789012345678', '2024-01-02', '42123.456789012345')",
+            ],
+        )
+        .await;
+
+        // stored text goes through the view untouched, floating point would keep ~15 digits
+        let row = &portfolio_table(&db.pool).await.unwrap()[0];
+        assert_eq!(
+            row.quantity,
+            Decimal::from_str("0.123456789012345678").unwrap()
+        );
+        assert_eq!(
+            row.buy_price,
+            Decimal::from_str("42123.456789012345").unwrap()
+        );
+        // products are computed in decimal arithmetic, not by the view in floating point
+        assert_eq!(row.buy_value, row.quantity * row.buy_price);
+        assert_eq!(row.market_value, row.buy_value);
+        assert_eq!(row.pl, Decimal::ZERO);
+    }
+
+    // daily bar at the new york open
+    fn bar(datetime: OffsetDateTime, close: f64) -> QuoteItem {
+        QuoteItem {
+            datetime: datetime.to_timezone(timezones::db::america::NEW_YORK),
+            timezone: "America/New_York".to_string(),
+            open: close - 1.0,
+            high: close + 1.0,
+            low: close - 2.0,
+            close,
+            adjclose: close,
+            volume: 1000,
+        }
+    }
+
+    #[tokio::test]
+    async fn sqlite_instruments() {
+        let db = super::super::sqlite("instruments").await;
+        db.migrate().await.unwrap();
+        upsert_instrument(&db.pool, &Instrument::new("AAPL", "usd"))
+            .await
+            .unwrap();
+        let apple = Instrument {
+            name: Some("Apple Inc.".to_string()),
+            exchange: Some("NMS".to_string()),
+            ..Instrument::new("AAPL", "USD")
+        };
+        upsert_instrument(&db.pool, &apple).await.unwrap();
+
+        assert_eq!(get_instrument(&db.pool, "AAPL").await.unwrap(), apple);
+        assert!(matches!(
+            get_instrument(&db.pool, "MSFT").await,
+            Err(DbQueryError::Notfound)
+        ));
+    }
+
+    #[tokio::test]
+    async fn sqlite_upsert_and_load_quotes() {
+        let db = super::super::sqlite("upsert_quotes").await;
+        db.migrate().await.unwrap();
+
+        // prices reference a stored instrument
+        let quotes = Array1::from_vec(vec![
+            bar(datetime!(2024-06-03 13:30 UTC), 100.0),
+            bar(datetime!(2024-06-04 13:30 UTC), 101.0),
+        ]);
+        assert!(upsert_quotes(&db.pool, "AAPL", "1d", &quotes)
+            .await
+            .is_err());
+
+        upsert_instrument(&db.pool, &Instrument::new("AAPL", "USD"))
+            .await
+            .unwrap();
+        upsert_quotes(&db.pool, "AAPL", "1d", &quotes)
+            .await
+            .unwrap();
+        // overlapping bar replaces the stored one
+        let update = Array1::from_vec(vec![
+            bar(datetime!(2024-06-04 13:30 UTC), 102.0),
+            bar(datetime!(2024-06-05 13:30 UTC), 103.0),
+        ]);
+        upsert_quotes(&db.pool, "AAPL", "1d", &update)
+            .await
+            .unwrap();
+        // same datetime on another interval is a different bar
+        upsert_quotes(&db.pool, "AAPL", "1h", &update)
+            .await
+            .unwrap();
+
+        let stored = load_quotes(
+            &db.pool,
+            "AAPL",
+            "1d",
+            date!(2024 - 06 - 01),
+            date!(2024 - 06 - 30),
+        )
+        .await
+        .unwrap();
+        assert_eq!(stored.len(), 3);
+        assert_eq!(
+            stored.iter().map(|q| q.close).collect::<Vec<f64>>(),
+            vec![100.0, 102.0, 103.0]
+        );
+        assert_eq!(stored[0].datetime, quotes[0].datetime);
+        assert_eq!(stored[0].datetime.offset(), quotes[0].datetime.offset());
+        assert_eq!(stored[0].timezone, "America/New_York");
+        assert_eq!(stored[0].volume, 1000);
+
+        let sliced = load_quotes(
+            &db.pool,
+            "AAPL",
+            "1d",
+            date!(2024 - 06 - 04),
+            date!(2024 - 06 - 04),
+        )
+        .await
+        .unwrap();
+        assert_eq!(sliced.len(), 1);
+        assert_eq!(sliced[0].close, 102.0);
+    }
+
+    #[tokio::test]
+    async fn sqlite_volume_out_of_range() {
+        let db = super::super::sqlite("volume_out_of_range").await;
+        db.migrate().await.unwrap();
+        upsert_instrument(&db.pool, &Instrument::new("AAPL", "USD"))
+            .await
+            .unwrap();
+
+        let huge = QuoteItem {
+            volume: u64::MAX,
+            ..bar(datetime!(2024-06-04 13:30 UTC), 101.0)
+        };
+        let quotes = Array1::from_vec(vec![bar(datetime!(2024-06-03 13:30 UTC), 100.0), huge]);
+        match upsert_quotes(&db.pool, "AAPL", "1d", &quotes).await {
+            Err(DbQueryError::VolumeOutOfRange(ticker, datetime, volume)) => {
+                assert_eq!(ticker, "AAPL");
+                assert_eq!(datetime, quotes[1].datetime);
+                assert_eq!(volume, u64::MAX);
+            }
+            r => panic!("unexpected result: {r:?}"),
+        }
+        // nothing is written, not even the valid bar
+        let stored = load_quotes(
+            &db.pool,
+            "AAPL",
+            "1d",
+            date!(2024 - 06 - 01),
+            date!(2024 - 06 - 30),
+        )
+        .await
+        .unwrap();
+        assert!(stored.is_empty());
+    }
+
+    #[tokio::test]
+    async fn sqlite_missing_ranges() {
+        let db = super::super::sqlite("missing_ranges").await;
+        db.migrate().await.unwrap();
+        upsert_instrument(&db.pool, &Instrument::new("AAPL", "USD"))
+            .await
+            .unwrap();
+
+        // nothing stored: the whole range is missing
+        assert_eq!(
+            missing_ranges(
+                &db.pool,
+                "AAPL",
+                "1d",
+                date!(2024 - 06 - 03),
+                date!(2024 - 06 - 14),
+                &[]
+            )
+            .await
+            .unwrap(),
+            vec![(date!(2024 - 06 - 03), date!(2024 - 06 - 14))]
+        );
+
+        // stored: tue 4, wed 5, tue 11
+        let quotes = Array1::from_vec(vec![
+            bar(datetime!(2024-06-04 13:30 UTC), 100.0),
+            bar(datetime!(2024-06-05 13:30 UTC), 101.0),
+            bar(datetime!(2024-06-11 13:30 UTC), 102.0),
+        ]);
+        upsert_quotes(&db.pool, "AAPL", "1d", &quotes)
+            .await
+            .unwrap();
+        assert_eq!(
+            missing_ranges(
+                &db.pool,
+                "AAPL",
+                "1d",
+                date!(2024 - 06 - 03),
+                date!(2024 - 06 - 16),
+                &weekends(date!(2024 - 06 - 03), date!(2024 - 06 - 16))
+            )
+            .await
+            .unwrap(),
+            vec![
+                (date!(2024 - 06 - 03), date!(2024 - 06 - 03)),
+                (date!(2024 - 06 - 06), date!(2024 - 06 - 10)),
+                (date!(2024 - 06 - 12), date!(2024 - 06 - 14)),
+            ]
+        );
+        // other intervals are tracked separately
+        assert_eq!(
+            missing_ranges(
+                &db.pool,
+                "AAPL",
+                "1h",
+                date!(2024 - 06 - 04),
+                date!(2024 - 06 - 04),
+                &[]
+            )
+            .await
+            .unwrap()
+            .len(),
+            1
+        );
+        // exchange holidays (thu 6, fri 7) are not missing and do not split a range
+        assert_eq!(
+            missing_ranges(
+                &db.pool,
+                "AAPL",
+                "1d",
+                date!(2024 - 06 - 03),
+                date!(2024 - 06 - 16),
+                &[
+                    weekends(date!(2024 - 06 - 03), date!(2024 - 06 - 16)),
+                    vec![
+                        date!(2024 - 06 - 03),
+                        date!(2024 - 06 - 06),
+                        date!(2024 - 06 - 07)
+                    ]
+                ]
+                .concat()
+            )
+            .await
+            .unwrap(),
+            vec![
+                (date!(2024 - 06 - 10), date!(2024 - 06 - 10)),
+                (date!(2024 - 06 - 12), date!(2024 - 06 - 14)),
+            ]
+        );
+        // without closed days (24/7 instruments) weekend gaps are missing too
+        assert_eq!(
+            missing_ranges(
+                &db.pool,
+                "AAPL",
+                "1d",
+                date!(2024 - 06 - 03),
+                date!(2024 - 06 - 16),
+                &[]
+            )
+            .await
+            .unwrap(),
+            vec![
+                (date!(2024 - 06 - 03), date!(2024 - 06 - 03)),
+                (date!(2024 - 06 - 06), date!(2024 - 06 - 10)),
+                (date!(2024 - 06 - 12), date!(2024 - 06 - 16)),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn sqlite_transactions() {
+        let db = super::super::sqlite("transactions").await;
+        db.migrate().await.unwrap();
+        upsert_instrument(&db.pool, &Instrument::new("AAPL", "USD"))
+            .await
+            .unwrap();
+        let day = |d| NaiveDate::from_ymd_opt(2024, 6, d).unwrap();
+
+        let buy = Transaction {
+            fees: Decimal::new(150, 2),
+            note: Some("first lot".to_string()),
+            ..Transaction::trade(
+                TransactionKind::Buy,
+                "AAPL",
+                day(4),
+                Decimal::new(25, 1),
+                Decimal::new(19025, 2),
+                "USD",
+            )
+        };
+        let deposit = Transaction {
+            amount: Decimal::from(1000),
+            ..Transaction::new(TransactionKind::Deposit, day(3), "usd")
+        };
+        let dividend = Transaction {
+            ticker: Some("AAPL".to_string()),
+            amount: Decimal::new(125, 2),
+            ..Transaction::new(TransactionKind::Dividend, day(4), "USD")
+        };
+        let buy_id = insert_transaction(&db.pool, &buy).await.unwrap();
+        let deposit_id = insert_transaction(&db.pool, &deposit).await.unwrap();
+        let dividend_id = insert_transaction(&db.pool, &dividend).await.unwrap();
+        assert!(buy_id < deposit_id && deposit_id < dividend_id);
+
+        // sorted by trade date, then insertion order
+        let all = list_transactions(&db.pool, None).await.unwrap();
+        assert_eq!(
+            all.iter().map(|t| t.id.unwrap()).collect::<Vec<i64>>(),
+            vec![deposit_id, buy_id, dividend_id]
+        );
+        assert_eq!(
+            all[1],
+            Transaction {
+                id: Some(buy_id),
+                ..buy
+            }
+        );
+        assert_eq!(all[0].ticker, None);
+        assert_eq!(all[0].currency, "USD");
+
+        let aapl = list_transactions(&db.pool, Some("AAPL")).await.unwrap();
+        assert_eq!(aapl.len(), 2);
+        assert_eq!(aapl[1].kind, TransactionKind::Dividend);
+
+        delete_transaction(&db.pool, buy_id).await.unwrap();
+        assert!(matches!(
+            delete_transaction(&db.pool, buy_id).await,
+            Err(DbQueryError::Notfound)
+        ));
+        assert_eq!(list_transactions(&db.pool, None).await.unwrap().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn sqlite_transaction_decimals_stored_as_text() {
+        let db = super::super::sqlite("transaction_decimals").await;
+        db.migrate().await.unwrap();
+        // more significant digits than f64 holds
+        let price: Decimal = "1234567890.123456789012345678".parse().unwrap();
+        let buy = Transaction::trade(
+            TransactionKind::Buy,
+            "AAPL",
+            NaiveDate::from_ymd_opt(2024, 6, 4).unwrap(),
+            Decimal::new(1, 1),
+            price,
+            "USD",
+        );
+        upsert_instrument(&db.pool, &Instrument::new("AAPL", "USD"))
+            .await
+            .unwrap();
+        insert_transaction(&db.pool, &buy).await.unwrap();
+
+        let DbPool::Sqlite(p) = &db.pool else {
+            panic!("expected sqlite pool")
+        };
+        let stored: (String, String, String) =
+            sqlx::query_as("SELECT typeof(price), price, quantity FROM transactions")
+                .fetch_one(p)
+                .await
+                .unwrap();
+        assert_eq!(
+            stored,
+            (
+                "text".to_string(),
+                "1234567890.123456789012345678".to_string(),
+                "0.1".to_string()
+            )
+        );
+        assert_eq!(
+            list_transactions(&db.pool, None).await.unwrap()[0].price,
+            price
+        );
+    }
+
+    #[tokio::test]
+    async fn sqlite_ledger_decimal_round_trip() {
+        let db = super::super::sqlite("ledger_round_trip").await;
+        db.migrate().await.unwrap();
+        upsert_instrument(&db.pool, &Instrument::new("BTC-USD", "USD"))
+            .await
+            .unwrap();
+        // 0.1 + 0.2 is not 0.3 in floating point
+        for (day, quantity) in [(2, Decimal::new(1, 1)), (3, Decimal::new(2, 1))] {
+            let buy = Transaction::trade(
+                TransactionKind::Buy,
+                "BTC-USD",
+                NaiveDate::from_ymd_opt(2024, 1, day).unwrap(),
+                quantity,
+                Decimal::new(1, 1),
+                "USD",
+            );
+            insert_transaction(&db.pool, &buy).await.unwrap();
+        }
+
+        let ledger = list_transactions(&db.pool, Some("BTC-USD")).await.unwrap();
+        assert_eq!(ledger[0].quantity, Decimal::new(1, 1));
+        let p = Portfolio::from_transactions_decimal(&ledger).unwrap();
+        assert_eq!(p.positions[0].quantity, Decimal::new(3, 1));
+        assert_eq!(p.positions[0].buy_value, Decimal::new(3, 2));
+        assert_eq!(p.positions[0].buy_price, Decimal::new(1, 1));
+    }
+}
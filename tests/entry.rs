@@ -1,5 +1,6 @@
 mod commons;
 mod data;
 mod database;
+mod error;
 mod portfolio;
 mod quantitative;
@@ -0,0 +1,42 @@
+use std::error::Error as StdError;
+
+use ndarray::Array1;
+use rs_quant::commons::date::{parse_date, DateType};
+use rs_quant::data::panel::PricePanel;
+use rs_quant::data::yahoo::{MultiQuoteItem, YahooErr};
+use rs_quant::quantitative::returns::ReturnType;
+use rs_quant::Error;
+
+// mixes data, date and quantitative errors through `?`
+fn panel_returns(ticker: &str, start: &str) -> rs_quant::Result<f64> {
+    parse_date(start, DateType::Start)?;
+    let panel = PricePanel::from(&Array1::<MultiQuoteItem>::from_vec(vec![]));
+    let returns = panel.returns(ReturnType::Arithmetic)?;
+    Ok(returns.column(ticker)?[0])
+}
+
+#[test]
+fn convert_module_errors() {
+    assert!(matches!(
+        panel_returns("AAPL", "2024-13-01"),
+        Err(Error::DateTime(_))
+    ));
+    assert!(matches!(
+        panel_returns("AAPL", "2024-01-01"),
+        Err(Error::Returns(_))
+    ));
+}
+
+#[test]
+fn preserve_source_chain() {
+    let json = serde_json::from_str::<u32>("x").unwrap_err();
+    let message = json.to_string();
+    let err = Error::from(YahooErr::DeserializationFailed(json));
+    assert_eq!(
+        err.to_string(),
+        format!("Failed to deserialize from yahoo! finance: {}", message)
+    );
+    let source = err.source().expect("missing source");
+    assert_eq!(source.to_string(), message);
+    assert!(source.downcast_ref::<serde_json::Error>().is_some());
+}
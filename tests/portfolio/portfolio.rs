@@ -1,6 +1,11 @@
+use chrono::NaiveDate;
+use ndarray::Array1;
+use rs_quant::data::fundamentals::QuoteSummary;
 use rs_quant::database::connection::DbConnection;
-use rs_quant::database::queries::portfolio_table;
-use rs_quant::portfolio::portfolio::Portfolio;
+use rs_quant::database::queries::{portfolio_table, Transaction, TransactionKind, VWPortfolio};
+use rs_quant::portfolio::portfolio::{Portfolio, PortfolioError};
+use rs_quant::portfolio::position::Position;
+use rust_decimal::Decimal;
 
 #[test]
 fn new_portfolio() {
@@ -36,12 +41,12 @@ async fn from_db() {
     // check individual position for first and seventh positions
     let first_pos = &p.positions[0];
     assert_eq!(first_pos.symbol, "EIMI.MI");
-    assert_eq!(first_pos.quantity, 15);
+    assert_eq!(first_pos.quantity, 15.0);
     assert_eq!(first_pos.buy_value, 455.02);
 
     let six_pos = &p.positions[4];
     assert_eq!(six_pos.symbol, "NVDA");
-    assert_eq!(six_pos.quantity, 13);
+    assert_eq!(six_pos.quantity, 13.0);
     assert_eq!(six_pos.buy_value, 1474.230);
 }
 
@@ -80,3 +85,274 @@ async fn weights() {
     portfolio.weights();
     assert_ne!(portfolio.positions[0].weight, 0f64);
 }
+
+#[test]
+fn sector_exposure() {
+    let summary = |symbol: &str, sector: Option<&str>| QuoteSummary {
+        symbol: symbol.to_string(),
+        name: None,
+        currency: None,
+        exchange: None,
+        sector: sector.map(str::to_string),
+        industry: None,
+        market_cap: None,
+        trailing_pe: None,
+        forward_pe: None,
+        trailing_eps: None,
+        forward_eps: None,
+        beta: None,
+        shares_outstanding: None,
+    };
+    let mut p = Portfolio::new();
+    p.positions = Array1::from_vec(vec![
+        Position::new("AAPL", 1.0, "USD", "2024-01-01", 100.0, 100.0),
+        Position::new("MSFT", 1.0, "USD", "2024-01-01", 100.0, 100.0),
+        Position::new("XOM", 1.0, "USD", "2024-01-01", 100.0, 100.0),
+        Position::new("VUAA.MI", 1.0, "EUR", "2024-01-01", 100.0, 100.0),
+    ]);
+    for (pos, weight) in p.positions.iter_mut().zip([0.3, 0.2, 0.1, 0.4]) {
+        pos.weight = weight;
+    }
+
+    let exposure = p.sector_exposure(&[
+        summary("AAPL", Some("Technology")),
+        summary("MSFT", Some("Technology")),
+        summary("XOM", Some("Energy")),
+        summary("VUAA.MI", None),
+    ]);
+    assert_eq!(exposure.len(), 3);
+    assert_eq!(exposure["Technology"], 0.5);
+    assert_eq!(exposure["Energy"], 0.1);
+    assert_eq!(exposure["Unknown"], 0.4);
+}
+
+fn trade(kind: TransactionKind, ticker: &str, day: u32, quantity: i64, price: i64) -> Transaction {
+    Transaction::trade(
+        kind,
+        ticker,
+        NaiveDate::from_ymd_opt(2024, 1, day).unwrap(),
+        Decimal::from(quantity),
+        Decimal::from(price),
+        "USD",
+    )
+}
+
+#[test]
+fn from_transactions() {
+    let ledger = vec![
+        // entries are replayed in trade date order
+        trade(TransactionKind::Sell, "AAPL", 20, 5, 130),
+        Transaction {
+            fees: Decimal::from(10),
+            ..trade(TransactionKind::Buy, "AAPL", 2, 10, 100)
+        },
+        trade(TransactionKind::Buy, "AAPL", 10, 10, 120),
+        trade(TransactionKind::Split, "AAPL", 25, 2, 0),
+        Transaction {
+            ticker: Some("AAPL".to_string()),
+            amount: Decimal::from(5),
+            ..Transaction::new(
+                TransactionKind::Dividend,
+                NaiveDate::from_ymd_opt(2024, 1, 26).unwrap(),
+                "USD",
+            )
+        },
+        trade(TransactionKind::Buy, "MSFT", 3, 4, 300),
+        // closed positions are dropped, reopening starts a new position
+        trade(TransactionKind::Buy, "NVDA", 3, 2, 500),
+        trade(TransactionKind::Sell, "NVDA", 4, 2, 550),
+        trade(TransactionKind::Buy, "NVDA", 15, 1, 600),
+    ];
+    let p = Portfolio::from_transactions(&ledger).unwrap();
+    assert_eq!(p.positions.len(), 3);
+
+    // 20 shares at 2210 total cost, 5 sold at average cost, then 2:1 split
+    let aapl = &p.positions[0];
+    assert_eq!(aapl.symbol, "AAPL");
+    assert_eq!(aapl.quantity, 30.0);
+    assert_eq!(aapl.buy_value, 1657.5);
+    assert_eq!(aapl.buy_price, 55.25);
+    assert_eq!(aapl.buy_date, "2024-01-02");
+    assert_eq!(aapl.market_value, aapl.buy_value);
+
+    let nvda = &p.positions[2];
+    assert_eq!(nvda.quantity, 1.0);
+    assert_eq!(nvda.buy_date, "2024-01-15");
+    assert_eq!(nvda.buy_value, 600.0);
+
+    let total = p.positions.iter().map(|p| p.weight).sum::<f64>();
+    assert!((total - 1.0).abs() < 1e-12);
+}
+
+#[test]
+fn from_transactions_oversold() {
+    let ledger = vec![
+        trade(TransactionKind::Buy, "AAPL", 2, 10, 100),
+        trade(TransactionKind::Sell, "AAPL", 3, 11, 100),
+    ];
+    assert!(matches!(
+        Portfolio::from_transactions(&ledger),
+        Err(PortfolioError::InvalidLedger(_))
+    ));
+}
+
+#[test]
+fn from_transactions_non_positive_quantity() {
+    let invalid = [
+        // zero quantity sell on an empty holding
+        trade(TransactionKind::Sell, "AAPL", 2, 0, 100),
+        trade(TransactionKind::Buy, "AAPL", 2, -10, 100),
+        trade(TransactionKind::Sell, "AAPL", 3, -5, 100),
+        trade(TransactionKind::Split, "AAPL", 4, 0, 0),
+        trade(TransactionKind::Split, "AAPL", 4, -2, 0),
+    ];
+    for t in invalid {
+        let ledger = vec![trade(TransactionKind::Buy, "AAPL", 1, 10, 100), t];
+        let r = Portfolio::from_transactions_decimal(&ledger);
+        assert!(
+            matches!(&r, Err(PortfolioError::InvalidLedger(_))),
+            "ledger: {:?}, result: {:?}",
+            ledger,
+            r
+        );
+    }
+}
+
+#[test]
+fn from_transactions_overflow() {
+    let overflowing = [
+        // cost of the buy
+        Transaction {
+            id: Some(7),
+            quantity: Decimal::MAX,
+            ..trade(TransactionKind::Buy, "AAPL", 2, 0, 2)
+        },
+        // quantity after the split
+        Transaction {
+            id: Some(7),
+            quantity: Decimal::MAX,
+            ..trade(TransactionKind::Split, "AAPL", 2, 0, 0)
+        },
+    ];
+    for t in overflowing {
+        let ledger = vec![trade(TransactionKind::Buy, "AAPL", 1, 10, 100), t];
+        match Portfolio::from_transactions_decimal(&ledger) {
+            Err(PortfolioError::InvalidLedger(msg)) => {
+                assert!(msg.contains("transaction 7"), "{}", msg)
+            }
+            r => panic!("ledger: {:?}, result: {:?}", ledger, r),
+        }
+    }
+}
+
+#[test]
+fn from_transactions_sell_fees_ignored() {
+    let ledger = vec![
+        trade(TransactionKind::Buy, "AAPL", 2, 10, 100),
+        Transaction {
+            fees: Decimal::from(15),
+            ..trade(TransactionKind::Sell, "AAPL", 3, 4, 120)
+        },
+    ];
+    let p = Portfolio::from_transactions_decimal(&ledger).unwrap();
+    assert_eq!(p.positions[0].quantity, Decimal::from(6));
+    assert_eq!(p.positions[0].buy_value, Decimal::from(600));
+}
+
+fn vw_row(
+    ticker: &str,
+    quantity: Decimal,
+    buy_price: Decimal,
+    market_price: Decimal,
+) -> VWPortfolio {
+    let buy_value = quantity * buy_price;
+    let market_value = quantity * market_price;
+    VWPortfolio {
+        ticker: ticker.to_string(),
+        quantity,
+        currency: "USD".to_string(),
+        buy_date: NaiveDate::from_ymd_opt(2024, 1, 2).unwrap(),
+        buy_price,
+        buy_value,
+        market_price,
+        market_value,
+        pl: market_value - buy_value,
+        pl_pct: market_value / buy_value - Decimal::ONE,
+    }
+}
+
+#[test]
+fn from_database_fractional_quantity() {
+    let p = Portfolio::from_database(vec![
+        vw_row(
+            "VUAA.MI",
+            Decimal::new(25, 1),
+            Decimal::from(100),
+            Decimal::from(110),
+        ),
+        vw_row(
+            "AAPL",
+            Decimal::from(3),
+            Decimal::new(1505, 1),
+            Decimal::from(180),
+        ),
+    ]);
+    assert_eq!(p.positions[0].quantity, 2.5);
+    assert_eq!(p.positions[0].market_value, 275.0);
+    assert_eq!(p.positions[1].buy_value, 451.5);
+    assert_eq!(p.positions[0].pl_pct, 0.1);
+}
+
+#[test]
+fn decimal_portfolio() {
+    // 0.1 + 0.2 is not 0.3 in floating point
+    let mut p = Portfolio::from_database_decimal(vec![
+        vw_row("A", Decimal::ONE, Decimal::new(1, 1), Decimal::new(1, 1)),
+        vw_row("B", Decimal::ONE, Decimal::new(2, 1), Decimal::new(2, 1)),
+    ]);
+    assert_eq!(p.total_investments(), Decimal::new(3, 1));
+    assert_eq!(p.total_pl(), Decimal::ZERO);
+
+    p.positions[1].market_price = Decimal::new(6, 1);
+    p.positions[1].equity();
+    p.positions[1].pl();
+    p.positions[1].pl_pct();
+    p.weights();
+    assert_eq!(p.positions[1].pl_pct, Decimal::from(2));
+    assert_eq!(p.positions[1].weight.round_dp(4), Decimal::new(8571, 4));
+    assert_eq!(p.total_mkt_val(), Decimal::new(7, 1));
+
+    let f = p.to_f64();
+    assert_eq!(f.positions[1].market_value, 0.6);
+    assert_eq!(f.positions[1].pl, 0.4);
+}
+
+#[test]
+fn from_transactions_fractional_quantity() {
+    let ledger = vec![
+        Transaction::trade(
+            TransactionKind::Buy,
+            "BTC-USD",
+            NaiveDate::from_ymd_opt(2024, 1, 2).unwrap(),
+            Decimal::new(5, 1),
+            Decimal::from(40000),
+            "USD",
+        ),
+        Transaction::trade(
+            TransactionKind::Buy,
+            "BTC-USD",
+            NaiveDate::from_ymd_opt(2024, 1, 3).unwrap(),
+            Decimal::new(25, 2),
+            Decimal::from(44000),
+            "USD",
+        ),
+    ];
+    let exact = Portfolio::from_transactions_decimal(&ledger).unwrap();
+    assert_eq!(exact.positions[0].quantity, Decimal::new(75, 2));
+    assert_eq!(exact.total_investments(), Decimal::from(31000));
+
+    let p = Portfolio::from_transactions(&ledger).unwrap();
+    assert_eq!(p.positions[0].quantity, 0.75);
+    assert_eq!(p.positions[0].buy_value, 31000.0);
+    assert_eq!(p.positions[0].weight, 1.0);
+}
@@ -1,9 +1,11 @@
 use rs_quant::portfolio::position::Position;
 
+use crate::data::common::yahoo::{mock_server, mount, provider};
+
 #[test]
 fn position_new() {
     let symbol = "AAPL";
-    let quantity = 100;
+    let quantity = 100.0;
     let currency = "USD";
     let buy_date = "2023-12-01";
     let buy_price = 150.0;
@@ -13,7 +15,7 @@ fn position_new() {
     let pos = Position::new(symbol, quantity, currency, buy_date, buy_price, buy_value);
     // check assigned value are correct
     assert_eq!(pos.symbol, "AAPL");
-    assert_eq!(pos.quantity, 100);
+    assert_eq!(pos.quantity, 100.0);
     assert_eq!(pos.currency, "USD");
     assert_eq!(pos.buy_date, "2023-12-01");
     assert_eq!(pos.buy_price, 150.0);
@@ -30,7 +32,7 @@ fn position_new() {
 async fn update_mkt_price() {
     let mut position = Position {
         symbol: "AAPL".to_string(),
-        quantity: 10,
+        quantity: 10.0,
         currency: "USD".to_string(),
         buy_date: "2023-12-01".to_string(),
         buy_price: 150.0,
@@ -42,15 +44,24 @@ async fn update_mkt_price() {
         weight: 0.0,
     };
 
-    position.update_mkt_price().await.unwrap();
-    assert_ne!(position.market_price, 0.0);
+    let server = mock_server().await;
+    mount(
+        &server,
+        "/v8/finance/chart/AAPL",
+        200,
+        include_str!("../fixtures/yahoo/chart_daily.json"),
+    )
+    .await;
+    position.update_mkt_price(&provider(&server)).await.unwrap();
+    assert_eq!(position.market_price, 189.3);
 }
 
 #[test]
+#[allow(clippy::let_unit_value)]
 fn pl() {
     let mut position = Position {
         symbol: "AAPL".to_string(),
-        quantity: 10,
+        quantity: 10.0,
         currency: "USD".to_string(),
         buy_date: "2023-12-01".to_string(),
         buy_price: 150.0,
@@ -67,10 +78,11 @@ fn pl() {
 }
 
 #[test]
+#[allow(clippy::let_unit_value)]
 fn pl_pct() {
     let mut position = Position {
         symbol: "AAPL".to_string(),
-        quantity: 10,
+        quantity: 10.0,
         currency: "USD".to_string(),
         buy_date: "2023-12-01".to_string(),
         buy_price: 150.0,
@@ -1,14 +1,19 @@
 use std::collections::HashMap;
 
 use ndarray::array;
-use rs_quant::data::yahoo::{MultiQuoteItem, QuoteItem, Yahoo};
+use rs_quant::commons::format::Round;
+use rs_quant::data::yahoo::{MultiQuoteItem, QuoteItem};
 use rs_quant::quantitative::returns::{ReturnType, Returns};
+use time::macros::{date, datetime};
+
+use crate::data::common::yahoo::{mock_server, mount, provider};
 
 #[test]
 fn returns() {
     let arr = array![
         QuoteItem {
-            datetime: "2024-02-01".to_string(),
+            datetime: datetime!(2024-02-01 0:00 UTC),
+            timezone: "UTC".to_string(),
             adjclose: 100.0,
             open: 0.0,
             high: 0.0,
@@ -17,7 +22,8 @@ fn returns() {
             volume: 0
         },
         QuoteItem {
-            datetime: "2024-02-02".to_string(),
+            datetime: datetime!(2024-02-02 0:00 UTC),
+            timezone: "UTC".to_string(),
             adjclose: 105.0,
             open: 0.0,
             high: 0.0,
@@ -26,7 +32,8 @@ fn returns() {
             volume: 0
         },
         QuoteItem {
-            datetime: "2024-02-02".to_string(),
+            datetime: datetime!(2024-02-02 0:00 UTC),
+            timezone: "UTC".to_string(),
             adjclose: 120.0,
             open: 0.0,
             high: 0.0,
@@ -35,7 +42,8 @@ fn returns() {
             volume: 0
         },
         QuoteItem {
-            datetime: "2024-02-02".to_string(),
+            datetime: datetime!(2024-02-02 0:00 UTC),
+            timezone: "UTC".to_string(),
             adjclose: 98.0,
             open: 0.0,
             high: 0.0,
@@ -46,31 +54,40 @@ fn returns() {
     ];
     assert!(arr.returns(ReturnType::Arithmetic).is_ok());
     assert_eq!(
-        arr.returns(ReturnType::Arithmetic).unwrap(),
+        arr.returns(ReturnType::Arithmetic).unwrap().rounded(4),
         array![
-            ("2024-02-02".to_string(), 0.05,),
-            ("2024-02-02".to_string(), 0.1429,),
-            ("2024-02-02".to_string(), -0.1833,)
+            (datetime!(2024-02-02 0:00 UTC), 0.05),
+            (datetime!(2024-02-02 0:00 UTC), 0.1429),
+            (datetime!(2024-02-02 0:00 UTC), -0.1833)
         ]
     )
 }
 
 #[tokio::test]
 async fn returns_from_yahoo() {
-    let conn = Yahoo::provider().unwrap();
-    let data = conn
+    let server = mock_server().await;
+    mount(
+        &server,
+        "/v8/finance/chart/AAPL",
+        200,
+        include_str!("../fixtures/yahoo/chart_daily.json"),
+    )
+    .await;
+    let data = provider(&server)
         .get_quotes("AAPL", None, None, Some("5d"), Some("1d"))
         .await
         .unwrap();
-    println!("{:#?}", data.returns(ReturnType::Arithmetic).unwrap());
-    assert!(data.returns(ReturnType::Arithmetic).is_ok());
+    let returns = data.returns(ReturnType::Arithmetic);
+    assert!(returns.is_ok(), "Error: {:?}", returns);
+    assert_eq!(returns.unwrap().len(), data.len() - 1);
 }
 
 #[test]
 fn cumulative_returns() {
     let data = array![
         QuoteItem {
-            datetime: "2024-02-01".to_string(),
+            datetime: datetime!(2024-02-01 0:00 UTC),
+            timezone: "UTC".to_string(),
             open: 100.0,
             high: 105.0,
             low: 95.0,
@@ -79,7 +96,8 @@ fn cumulative_returns() {
             volume: 1000,
         },
         QuoteItem {
-            datetime: "2024-02-02".to_string(),
+            datetime: datetime!(2024-02-02 0:00 UTC),
+            timezone: "UTC".to_string(),
             open: 102.0,
             high: 106.0,
             low: 98.0,
@@ -88,7 +106,8 @@ fn cumulative_returns() {
             volume: 1100,
         },
         QuoteItem {
-            datetime: "2024-02-03".to_string(),
+            datetime: datetime!(2024-02-03 0:00 UTC),
+            timezone: "UTC".to_string(),
             open: 104.0,
             high: 108.0,
             low: 100.0,
@@ -98,11 +117,14 @@ fn cumulative_returns() {
         }
     ];
 
-    let cumulative_returns = data.cumulative_returns(ReturnType::Arithmetic).unwrap();
+    let cumulative_returns = data
+        .cumulative_returns(ReturnType::Arithmetic)
+        .unwrap()
+        .rounded(4);
 
     let expected_returns = array![
-        ("2024-02-02".to_string(), 0.0196), // (104/102) - 1
-        ("2024-02-03".to_string(), 0.049),  // cumulative sum: 0.0196 + (107/104 - 1)
+        (datetime!(2024-02-02 0:00 UTC), 0.0196), // (104/102) - 1
+        (datetime!(2024-02-03 0:00 UTC), 0.049),  // cumulative sum: 0.0196 + (107/104 - 1)
     ];
 
     assert_eq!(cumulative_returns, expected_returns);
@@ -112,33 +134,34 @@ fn cumulative_returns() {
 fn test_cumulative_returns_multi_asset() {
     let prices = array![
         MultiQuoteItem {
-            date: "2024-02-01".to_string(),
+            date: date!(2024 - 02 - 01),
             prices: HashMap::from([("AAPL".to_string(), 150.0), ("GOOG".to_string(), 2800.0),]),
         },
         MultiQuoteItem {
-            date: "2024-02-02".to_string(),
+            date: date!(2024 - 02 - 02),
             prices: HashMap::from([("AAPL".to_string(), 155.0), ("GOOG".to_string(), 2856.0),]),
         },
         MultiQuoteItem {
-            date: "2024-02-03".to_string(),
+            date: date!(2024 - 02 - 03),
             prices: HashMap::from([("AAPL".to_string(), 158.0), ("GOOG".to_string(), 2900.0),]),
         }
     ];
 
     let result = prices
         .cumulative_returns_multiquote(ReturnType::Arithmetic)
-        .unwrap();
+        .unwrap()
+        .rounded(4);
 
     let expected = array![
         (
-            "2024-02-02".to_string(),
+            date!(2024 - 02 - 02),
             HashMap::from([
                 ("AAPL".to_string(), 0.0333), // (1.0 + 0.0333) - 1.0 = 0.0333
                 ("GOOG".to_string(), 0.02),   // (1.0 + 0.02) - 1.0 = 0.02
             ])
         ),
         (
-            "2024-02-03".to_string(),
+            date!(2024 - 02 - 03),
             HashMap::from([
                 ("AAPL".to_string(), 0.0533), // (1.0 + 0.0333) * (1.0 + 0.0129) - 1.0 = 0.0533
                 ("GOOG".to_string(), 0.0357), // (1.0 + 0.02) * (1.0 + 0.0154) - 1.0 = 0.0357